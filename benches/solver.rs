@@ -0,0 +1,147 @@
+// Compares the solver against the original implementation, which bounds checked every step,
+// cloned each die and tracked visited cells in a HashSet. Run with `cargo bench`.
+use boggle::boggle_utils::boggle_board::BoggleBoard;
+use boggle::boggle_utils::boggle_char::BoggleChar;
+use boggle::boggle_utils::boggle_dice;
+use boggle::utils::compact_trie::CompactTrie;
+use boggle::utils::dict_trie::{DictTrie, DictTrieNode};
+use boggle::utils::resources::{DictionaryLocation, DEFAULT_DICTIONARY};
+use boggle::utils::trie_manager;
+use boggle::utils::word_trie::WordTrie;
+use boggle::{BoggleSolver, SolverConfig};
+use std::collections::HashSet;
+use std::hint::black_box;
+use std::path::Path;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+const BOARDS_PER_SIZE: usize = 200;
+
+fn legacy_solve(board: &BoggleBoard, size: i32, trie: &DictTrie) -> HashSet<String> {
+    let mut words = HashSet::new();
+    for i in 0..size * size {
+        legacy_step_and_search(
+            board,
+            (i % size, i / size),
+            &mut HashSet::new(),
+            &mut words,
+            &mut vec![],
+            trie.get_start_node(),
+        );
+    }
+    words
+}
+
+fn legacy_step_and_search(
+    board: &BoggleBoard,
+    loc: (i32, i32),
+    seen: &mut HashSet<(i32, i32)>,
+    found: &mut HashSet<String>,
+    cur_word: &mut Vec<char>,
+    prev_node: &DictTrieNode,
+) {
+    if seen.contains(&loc) {
+        return;
+    }
+    let current_letter: BoggleChar = board.access((loc.1 as usize, loc.0 as usize));
+    let letters = current_letter.to_char_vec();
+    let mut cur_node = prev_node;
+    for (i, letter) in letters.iter().enumerate() {
+        match cur_node.get_child(letter) {
+            Some(node) => cur_node = node,
+            None => {
+                cur_word.truncate(cur_word.len() - i);
+                return;
+            }
+        }
+        cur_word.push(*letter);
+    }
+    seen.insert(loc);
+    if cur_word.len() > 2 && cur_node.end {
+        found.insert(cur_word.iter().collect());
+    }
+    let steps = vec![(1, 0), (0, 1), (-1, 0), (0, -1)];
+    for step in steps {
+        let new_pos = (loc.0 + step.0, loc.1 + step.1);
+        if !board.in_bounds(&new_pos) {
+            continue;
+        }
+        legacy_step_and_search(board, new_pos, seen, found, cur_word, cur_node);
+    }
+    seen.remove(&loc);
+    cur_word.truncate(cur_word.len() - letters.len());
+}
+
+// Benches build without debug assertions, so the checkout's resources folder has to be named
+fn dictionary_location() -> DictionaryLocation {
+    DictionaryLocation::new(
+        DEFAULT_DICTIONARY,
+        Some(Path::new(env!("CARGO_MANIFEST_DIR")).join("resources")),
+    )
+}
+
+fn main() {
+    let location = dictionary_location();
+    let start = Instant::now();
+    let trie =
+        trie_manager::load_trie(&location).expect("the benchmark needs the default dictionary");
+    println!(
+        "hash map trie loaded in {:.3}s",
+        start.elapsed().as_secs_f64()
+    );
+    let start = Instant::now();
+    let compact = trie_manager::load_compact_trie(&location).unwrap();
+    println!(
+        "compact trie loaded in {:.3}s ({} nodes, {} edges)",
+        start.elapsed().as_secs_f64(),
+        compact.node_count(),
+        compact.edge_count()
+    );
+
+    // No 6x6: those boards come off the Super Big Boggle dice, whose blank faces the legacy solver
+    // would walk straight through
+    for size in [4, 5, 8] {
+        let mut rand = boggle_dice::seeded_rng(size as u64);
+        let boards: Vec<BoggleBoard> = (0..BOARDS_PER_SIZE)
+            .map(|_| BoggleBoard::new(size, &mut rand).unwrap())
+            .collect();
+
+        let start = Instant::now();
+        for board in &boards {
+            black_box(legacy_solve(board, size, &trie));
+        }
+        let legacy = start.elapsed();
+        let hash_map = time_solver::<DictTrie>(size, &boards);
+        let compact = time_solver::<CompactTrie>(size, &boards);
+
+        println!(
+            "{}x{}: legacy {:>8.1} boards/s, hash map {:>8.1} boards/s ({:.2}x), compact {:>8.1} boards/s ({:.2}x)",
+            size,
+            size,
+            boards_per_second(legacy),
+            boards_per_second(hash_map),
+            legacy.as_secs_f64() / hash_map.as_secs_f64(),
+            boards_per_second(compact),
+            legacy.as_secs_f64() / compact.as_secs_f64()
+        );
+    }
+}
+
+fn time_solver<D: WordTrie>(size: i32, boards: &[BoggleBoard]) -> Duration {
+    let mut config = SolverConfig::new(size);
+    config.min_word_length = 3;
+    let dictionary = Arc::new(D::load(&dictionary_location()).unwrap());
+    let mut solver =
+        BoggleSolver::new(config, dictionary, &mut boggle_dice::seeded_rng(0)).unwrap();
+
+    let start = Instant::now();
+    for board in boards {
+        solver.set_board(board.clone()).unwrap();
+        black_box(solver.get_solutions());
+    }
+    start.elapsed()
+}
+
+fn boards_per_second(elapsed: Duration) -> f64 {
+    BOARDS_PER_SIZE as f64 / elapsed.as_secs_f64()
+}
@@ -0,0 +1,286 @@
+use rand::seq::SliceRandom;
+use rand::Rng;
+use rayon::prelude::*;
+use std::sync::Arc;
+
+use crate::boggle_utils::boggle_board::BoggleBoard;
+use crate::boggle_utils::boggle_char::BoggleChar;
+use crate::boggle_utils::boggle_dice::{self, DiceSet};
+use crate::utils::word_trie::WordTrie;
+use crate::{BoggleError, BoggleSolver, SolverConfig};
+
+// How hard to look for a high scoring board. Each chain is an independent simulated annealing run
+// from its own random board; the temperature falls geometrically from `start_temperature` to
+// `end_temperature`, and a start temperature of 0 makes it a plain hill climb.
+#[derive(Clone, Debug)]
+pub struct SearchOptions {
+    pub iterations: usize,
+    pub chains: usize,
+    pub start_temperature: f64,
+    pub end_temperature: f64,
+    // Only consider boards the configured dice, or the official set for the board size, can roll
+    pub real_dice: bool,
+}
+
+impl Default for SearchOptions {
+    fn default() -> Self {
+        Self {
+            iterations: 2000,
+            chains: rayon::current_num_threads(),
+            start_temperature: 50.0,
+            end_temperature: 0.5,
+            real_dice: false,
+        }
+    }
+}
+
+// A board being searched. With dice, each cell remembers which die sits there and which side is
+// up, so that moves can only ever produce a board the dice could have rolled.
+#[derive(Clone)]
+struct Candidate {
+    faces: Vec<BoggleChar>,
+    dice: Vec<(usize, usize)>,
+}
+
+impl Candidate {
+    fn roll<R: Rng + ?Sized>(
+        solver: &BoggleSolver<impl WordTrie>,
+        dice: Option<&DiceSet>,
+        rand: &mut R,
+    ) -> Self {
+        let Some(dice) = dice else {
+            return Self {
+                faces: solver.get_board().faces(),
+                dice: vec![],
+            };
+        };
+        let mut order: Vec<usize> = (0..dice.len()).collect();
+        order.shuffle(rand);
+        let mut candidate = Self {
+            faces: vec![],
+            dice: order
+                .into_iter()
+                .map(|die| (die, rand.gen_range(0..6)))
+                .collect(),
+        };
+        candidate.faces = candidate.dice_faces(dice);
+        candidate
+    }
+
+    fn dice_faces(&self, dice: &DiceSet) -> Vec<BoggleChar> {
+        self.dice
+            .iter()
+            .map(|&(die, side)| dice.dice()[die].faces()[side].clone())
+            .collect()
+    }
+
+    // Either swaps two cells or changes the letter in one, only ever touching the `open` cells
+    fn mutate<R: Rng + ?Sized>(
+        &self,
+        open: &[usize],
+        dice: Option<&DiceSet>,
+        rand: &mut R,
+    ) -> Self {
+        let mut next = self.clone();
+        let (a, b) = (
+            open[rand.gen_range(0..open.len())],
+            open[rand.gen_range(0..open.len())],
+        );
+        let swap = rand.gen_bool(0.5);
+        match dice {
+            Some(dice) => {
+                if swap {
+                    next.dice.swap(a, b);
+                } else {
+                    next.dice[a].1 = (next.dice[a].1 + rand.gen_range(1..6)) % 6;
+                }
+                next.faces = next.dice_faces(dice);
+            }
+            None => {
+                if swap {
+                    next.faces.swap(a, b);
+                } else {
+                    let letter = char::from(rand.gen_range(b'a'..=b'z'));
+                    next.faces[a] =
+                        BoggleChar::try_from(letter).expect("a-z are all board letters");
+                }
+            }
+        }
+        next
+    }
+}
+
+// Searches for the highest scoring board of the configured shape, running the chains in
+// parallel. Returns a solver holding the best board found, ready to report on.
+pub fn find_best_board<D: WordTrie>(
+    config: &SolverConfig,
+    dictionary: Arc<D>,
+    options: &SearchOptions,
+    seed: u64,
+) -> Result<BoggleSolver<D>, BoggleError> {
+    let dice = if options.real_dice {
+        let dice = config
+            .dice
+            .clone()
+            .or_else(|| DiceSet::official(config.cell_count()))
+            .ok_or_else(|| {
+                BoggleError::InvalidDice(format!(
+                    "there are no official dice for {} cells, pass a dice set",
+                    config.cell_count()
+                ))
+            })?;
+        dice.check_fits(config.cell_count())?;
+        Some(dice)
+    } else {
+        None
+    };
+
+    // The chains already keep every thread busy
+    let mut config = config.clone();
+    config.multi_thread = false;
+    // Drawing the chain seeds from the search seed keeps nearby seeds from sharing chains
+    let mut seeds = boggle_dice::seeded_rng(seed);
+    let chain_seeds: Vec<u64> = (0..options.chains.max(1)).map(|_| seeds.gen()).collect();
+    let results = chain_seeds
+        .into_par_iter()
+        .map(|chain_seed| {
+            run_chain(
+                &config,
+                Arc::clone(&dictionary),
+                options,
+                dice.as_ref(),
+                chain_seed,
+            )
+        })
+        .collect::<Result<Vec<(u32, BoggleSolver<D>)>, BoggleError>>()?;
+    let best = results
+        .into_iter()
+        .max_by_key(|(score, _)| *score)
+        .expect("there is always at least one chain");
+    Ok(best.1)
+}
+
+fn run_chain<D: WordTrie>(
+    config: &SolverConfig,
+    dictionary: Arc<D>,
+    options: &SearchOptions,
+    dice: Option<&DiceSet>,
+    seed: u64,
+) -> Result<(u32, BoggleSolver<D>), BoggleError> {
+    let mut rand = boggle_dice::seeded_rng(seed);
+    let mut solver = BoggleSolver::new(config.clone(), dictionary, &mut rand)?;
+    let mut current = Candidate::roll(&solver, dice, &mut rand);
+    let mut current_score = score(&mut solver, config, &current)?;
+    let mut best = (current_score, current.clone());
+    // Masked cells end up blocked whatever is put there, so moves only ever touch the open ones
+    let open: Vec<usize> = (0..config.cell_count())
+        .filter(|&cell| {
+            config
+                .mask
+                .as_ref()
+                .is_none_or(|mask| !mask.is_blocked(cell))
+        })
+        .collect();
+    let iterations = if open.is_empty() {
+        0
+    } else {
+        options.iterations
+    };
+
+    let cooling = if options.start_temperature > 0.0 {
+        (options.end_temperature / options.start_temperature).powf(1.0 / options.iterations as f64)
+    } else {
+        0.0
+    };
+    let mut temperature = options.start_temperature;
+    for _ in 0..iterations {
+        let next = current.mutate(&open, dice, &mut rand);
+        let next_score = score(&mut solver, config, &next)?;
+        // Always take a better board, and sometimes a worse one while it is still hot
+        let gain = next_score as f64 - current_score as f64;
+        if gain >= 0.0 || (temperature > 0.0 && rand.gen::<f64>() < (gain / temperature).exp()) {
+            current = next;
+            current_score = next_score;
+            if current_score > best.0 {
+                best = (current_score, current.clone());
+            }
+        }
+        temperature *= cooling;
+    }
+
+    score(&mut solver, config, &best.1)?;
+    Ok((best.0, solver))
+}
+
+fn score<D: WordTrie>(
+    solver: &mut BoggleSolver<D>,
+    config: &SolverConfig,
+    candidate: &Candidate,
+) -> Result<u32, BoggleError> {
+    let board = BoggleBoard::from_faces(candidate.faces.clone(), config.rows, config.cols)?
+        .with_grid(config.grid);
+    let board = match &config.mask {
+        Some(mask) => board.with_mask(mask)?,
+        None => board,
+    };
+    solver.set_board(board)?;
+    Ok(solver.max_score())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::dict_trie::DictTrie;
+
+    #[test]
+    fn finds_better_boards() {
+        let mut trie = DictTrie::new();
+        for word in [
+            "tea", "eat", "ate", "tee", "teat", "tate", "teeter", "treat", "rate",
+        ] {
+            trie.insert_word(word);
+        }
+        let dictionary = Arc::new(trie);
+        let mut config = SolverConfig::new(3);
+        config.diagonals = true;
+        let options = SearchOptions {
+            iterations: 300,
+            chains: 2,
+            ..SearchOptions::default()
+        };
+
+        let best = find_best_board(&config, Arc::clone(&dictionary), &options, 1).unwrap();
+        let random =
+            BoggleSolver::new(config.clone(), dictionary, &mut boggle_dice::seeded_rng(1)).unwrap();
+        assert!(best.max_score() > random.max_score());
+        assert!(best.get_possible_words().len() >= 4);
+
+        let real_dice = SearchOptions {
+            real_dice: true,
+            ..options
+        };
+        let dictionary = Arc::new(DictTrie::new());
+        assert!(matches!(
+            find_best_board(&config, dictionary, &real_dice, 1),
+            Err(BoggleError::InvalidDice(_))
+        ));
+    }
+
+    #[test]
+    fn moves_stay_on_open_cells() {
+        let start = Candidate {
+            faces: "abc#"
+                .chars()
+                .map(|c| BoggleChar::try_from(c).unwrap())
+                .collect(),
+            dice: vec![],
+        };
+        let mut rand = boggle_dice::seeded_rng(3);
+        let mut candidate = start.clone();
+        for _ in 0..100 {
+            candidate = candidate.mutate(&[0, 2], None, &mut rand);
+            assert_eq!(candidate.faces[1], start.faces[1]);
+            assert_eq!(candidate.faces[3], BoggleChar::Blocked);
+        }
+    }
+}
@@ -0,0 +1,95 @@
+use std::collections::HashMap;
+
+use super::boggle_char::BoggleChar;
+use super::boggle_report::BoardSummary;
+
+// What a rolled board has to offer before it is handed to players. The letter limits are checked
+// straight off the dice, the rest need the board solved first.
+#[derive(Clone, Debug)]
+pub struct BoardConstraints {
+    pub min_words: usize,
+    pub min_score: u32,
+    // Some word has to be at least this long
+    pub min_longest_word: usize,
+    // No letter may show up more than this many times
+    pub max_repeats: Option<usize>,
+    pub max_qu: Option<usize>,
+    // How many boards to roll before giving up
+    pub max_attempts: usize,
+}
+
+impl Default for BoardConstraints {
+    fn default() -> Self {
+        Self {
+            min_words: 0,
+            min_score: 0,
+            min_longest_word: 0,
+            max_repeats: None,
+            max_qu: None,
+            max_attempts: 10_000,
+        }
+    }
+}
+
+impl BoardConstraints {
+    pub fn allows_faces(&self, faces: &[BoggleChar]) -> bool {
+        let mut counts: HashMap<&BoggleChar, usize> = HashMap::new();
+        for face in faces {
+            if !matches!(face, BoggleChar::Blocked | BoggleChar::Wildcard) {
+                *counts.entry(face).or_default() += 1;
+            }
+        }
+        let qu = counts.get(&BoggleChar::Qu).copied().unwrap_or(0);
+        self.max_qu.is_none_or(|max| qu <= max)
+            && self
+                .max_repeats
+                .is_none_or(|max| counts.values().all(|&count| count <= max))
+    }
+
+    pub fn allows_summary(&self, summary: &BoardSummary) -> bool {
+        let longest = summary
+            .longest_word
+            .as_ref()
+            .map_or(0, |word| word.chars().count());
+        summary.word_count >= self.min_words
+            && summary.max_score >= self.min_score
+            && longest >= self.min_longest_word
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn checks_faces_and_results() {
+        let constraints = BoardConstraints {
+            min_words: 2,
+            min_longest_word: 5,
+            max_repeats: Some(2),
+            max_qu: Some(1),
+            ..BoardConstraints::default()
+        };
+        let faces = |board: &str| -> Vec<BoggleChar> {
+            board
+                .chars()
+                .map(|c| BoggleChar::try_from(c).unwrap())
+                .collect()
+        };
+        assert!(constraints.allows_faces(&faces("aabq#??")));
+        assert!(!constraints.allows_faces(&faces("aaab")));
+        assert!(!constraints.allows_faces(&faces("qqab")));
+
+        let summary = |word_count, longest: &str| BoardSummary {
+            line: 0,
+            board: String::new(),
+            word_count,
+            max_score: 10,
+            longest_word: Some(longest.to_string()),
+            error: None,
+        };
+        assert!(constraints.allows_summary(&summary(2, "tests")));
+        assert!(!constraints.allows_summary(&summary(1, "tests")));
+        assert!(!constraints.allows_summary(&summary(2, "test")));
+    }
+}
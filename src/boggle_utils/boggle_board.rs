@@ -1,56 +1,208 @@
+use super::boggle_graph::{BoggleGraph, Topology};
+use super::graph_board::GraphBoard;
 use super::{boggle_char::BoggleChar, boggle_dice};
+use crate::error::BoggleError;
+use rand::Rng;
 use serde::Serialize;
 use std::fmt;
+use std::str::FromStr;
+
+const HEX_PREFIX: &str = "hex:";
+
+// The shape of the cells. Hex boards use "odd-r" offset rows: every odd row sits half a cell to
+// the right of the rows either side of it, which gives each cell six neighbours.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Grid {
+    #[default]
+    Square,
+    Hex,
+}
+
+impl Grid {
+    // The (x, y) offsets to the cells next to one in `row`
+    pub fn steps(&self, row: usize, diagonals: bool) -> &'static [(i32, i32)] {
+        match self {
+            Grid::Square if diagonals => &[
+                (1, 0),
+                (0, 1),
+                (-1, 0),
+                (0, -1),
+                (1, 1),
+                (-1, 1),
+                (1, -1),
+                (-1, -1),
+            ],
+            Grid::Square => &[(1, 0), (0, 1), (-1, 0), (0, -1)],
+            // Hex cells already touch everything around them, so `diagonals` makes no difference
+            Grid::Hex if row.is_multiple_of(2) => {
+                &[(1, 0), (-1, 0), (-1, -1), (0, -1), (-1, 1), (0, 1)]
+            }
+            Grid::Hex => &[(1, 0), (-1, 0), (0, -1), (1, -1), (0, 1), (1, 1)],
+        }
+    }
+}
+
+impl FromStr for Grid {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "square" => Ok(Grid::Square),
+            "hex" => Ok(Grid::Hex),
+            _ => Err(format!("unknown grid '{}', expected square or hex", s)),
+        }
+    }
+}
+
+impl fmt::Display for Grid {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Grid::Square => write!(f, "square"),
+            Grid::Hex => write!(f, "hex"),
+        }
+    }
+}
 
 #[derive(Clone, Serialize)]
 pub struct BoggleBoard {
     board: Vec<Vec<BoggleChar>>,
-    board_size: i32,
+    rows: i32,
+    cols: i32,
+    grid: Grid,
 }
 
 impl BoggleBoard {
-    pub fn new(size: i32) -> Self {
-        let full_chars: Vec<BoggleChar> = boggle_dice::scramble_dice(size);
-        let mut board: Vec<Vec<BoggleChar>> = vec![];
-        for i in 0..size {
-            let mut row: Vec<BoggleChar> = vec![];
-            for j in 0..size {
-                let index: usize = (i * size + j).try_into().unwrap();
-                row.push(full_chars[index].clone());
-            }
-            board.push(row);
-        }
-        Self {
-            board,
-            board_size: size,
-        }
+    pub fn new<R: Rng + ?Sized>(size: i32, rand: &mut R) -> Result<Self, BoggleError> {
+        Self::new_rect(size, size, rand)
+    }
+
+    pub fn new_rect<R: Rng + ?Sized>(
+        rows: i32,
+        cols: i32,
+        rand: &mut R,
+    ) -> Result<Self, BoggleError> {
+        Self::new_grid(rows, cols, Grid::Square, rand)
+    }
+
+    pub fn new_grid<R: Rng + ?Sized>(
+        rows: i32,
+        cols: i32,
+        grid: Grid,
+        rand: &mut R,
+    ) -> Result<Self, BoggleError> {
+        check_dimensions(rows, cols)?;
+        let cells = rows as usize * cols as usize;
+        let full_chars = match grid {
+            Grid::Square => boggle_dice::scramble_dice(cells, rand),
+            Grid::Hex => boggle_dice::scramble_hex_dice(cells, rand),
+        };
+        Ok(Self::from_cells(full_chars, rows, cols).with_grid(grid))
     }
 
-    pub fn from(chars: Vec<char>, size: i32) -> Self {
+    pub fn from(chars: Vec<char>, size: i32) -> Result<Self, BoggleError> {
+        Self::from_rect(chars, size, size)
+    }
+
+    pub fn from_rect(chars: Vec<char>, rows: i32, cols: i32) -> Result<Self, BoggleError> {
+        check_dimensions(rows, cols)?;
+        let expected = rows as usize * cols as usize;
+        if chars.len() != expected {
+            return Err(BoggleError::SizeMismatch {
+                expected,
+                found: chars.len(),
+            });
+        }
         let full_chars: Vec<BoggleChar> = chars
             .into_iter()
-            .map(|c| BoggleChar::from(c.to_ascii_uppercase() as u8))
-            .collect();
-        let mut board: Vec<Vec<BoggleChar>> = vec![];
-        for i in 0..size {
-            let mut row: Vec<BoggleChar> = vec![];
-            for j in 0..size {
-                let index: usize = (i * size + j).try_into().unwrap();
-                row.push(full_chars[index].clone());
+            .map(BoggleChar::try_from)
+            .collect::<Result<_, _>>()?;
+        Self::from_faces(full_chars, rows, cols)
+    }
+
+    // Reads a board string. The letters can be given as "RxC:letters" (e.g. "2x3:abcdef"), as
+    // rows separated by slashes ("abc/def") or on their own, in which case the board is assumed
+    // to be `rows` by `cols`. A "hex:" prefix makes it a hex board whatever `grid` says.
+    pub fn parse(board: &str, rows: i32, cols: i32, grid: Grid) -> Result<Self, BoggleError> {
+        let prefix = board.get(..HEX_PREFIX.len());
+        if prefix.is_some_and(|prefix| prefix.eq_ignore_ascii_case(HEX_PREFIX)) {
+            return Self::parse(&board[HEX_PREFIX.len()..], rows, cols, Grid::Square)
+                .map(|board| board.with_grid(Grid::Hex));
+        }
+        Self::parse_letters(board, rows, cols).map(|board| board.with_grid(grid))
+    }
+
+    fn parse_letters(board: &str, rows: i32, cols: i32) -> Result<Self, BoggleError> {
+        let (rows, cols, faces) = split_layout(board, rows, cols)?;
+        let faces = faces
+            .iter()
+            .map(|face| face.parse())
+            .collect::<Result<Vec<BoggleChar>, BoggleError>>()?;
+        Self::from_faces(faces, rows, cols)
+    }
+
+    pub fn from_faces(faces: Vec<BoggleChar>, rows: i32, cols: i32) -> Result<Self, BoggleError> {
+        check_dimensions(rows, cols)?;
+        let expected = rows as usize * cols as usize;
+        if faces.len() != expected {
+            return Err(BoggleError::SizeMismatch {
+                expected,
+                found: faces.len(),
+            });
+        }
+        Ok(Self::from_cells(faces, rows, cols))
+    }
+
+    // Blocks off every cell the mask marks with '#' or '.'. The mask is written like a board
+    // string, e.g. "#??#/????" or "2x4:#??#????", and has to be the same shape as the board.
+    pub fn with_mask(mut self, mask: &BoardMask) -> Result<Self, BoggleError> {
+        if (mask.rows, mask.cols) != (self.rows, self.cols) {
+            return Err(BoggleError::SizeMismatch {
+                expected: self.rows as usize * self.cols as usize,
+                found: mask.blocked.len(),
+            });
+        }
+        for (cell, &blocked) in self.board.iter_mut().flatten().zip(&mask.blocked) {
+            if blocked {
+                *cell = BoggleChar::Blocked;
             }
-            board.push(row);
         }
+        Ok(self)
+    }
+
+    fn from_cells(full_chars: Vec<BoggleChar>, rows: i32, cols: i32) -> Self {
+        let board: Vec<Vec<BoggleChar>> = full_chars
+            .chunks(cols as usize)
+            .map(|row| row.to_vec())
+            .collect();
         Self {
             board,
-            board_size: size,
+            rows,
+            cols,
+            grid: Grid::Square,
         }
     }
 
+    pub fn with_grid(mut self, grid: Grid) -> Self {
+        self.grid = grid;
+        self
+    }
+
+    pub fn grid(&self) -> Grid {
+        self.grid
+    }
+
+    pub fn rows(&self) -> i32 {
+        self.rows
+    }
+
+    pub fn cols(&self) -> i32 {
+        self.cols
+    }
+
+    // Locations are (x, y), i.e. (column, row)
     pub fn in_bounds(&self, location: &(i32, i32)) -> bool {
-        location.0 >= 0
-            && location.0 <= self.board_size - 1
-            && location.1 >= 0
-            && location.1 <= self.board_size - 1
+        location.0 >= 0 && location.0 < self.cols && location.1 >= 0 && location.1 < self.rows
     }
 
     pub fn access(&self, coords: (usize, usize)) -> BoggleChar {
@@ -60,13 +212,382 @@ impl BoggleBoard {
     pub fn get_chars(&self) -> Vec<Vec<BoggleChar>> {
         self.board.clone()
     }
+
+    // The board in a format `parse` reads, one letter per die with Qu written as Q and other
+    // multi-letter faces in brackets. Boards that aren't square are prefixed with their
+    // dimensions, hex boards always are.
+    pub fn to_board_string(&self) -> String {
+        let letters: String = self
+            .board
+            .iter()
+            .flatten()
+            .map(|letter| match letter {
+                BoggleChar::Qu => String::from("q"),
+                BoggleChar::Blocked => String::from("#"),
+                BoggleChar::Multi(face) => format!("[{}]", face),
+                _ => letter.to_char_vec().into_iter().collect(),
+            })
+            .collect();
+        match self.grid {
+            Grid::Hex => format!("{}{}x{}:{}", HEX_PREFIX, self.rows, self.cols, letters),
+            Grid::Square if self.rows == self.cols => letters,
+            Grid::Square => format!("{}x{}:{}", self.rows, self.cols, letters),
+        }
+    }
+}
+
+// Which cells of a board are holes, read from a board string where '#' and '.' are blocked and
+// any other character is open
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BoardMask {
+    rows: i32,
+    cols: i32,
+    blocked: Vec<bool>,
+}
+
+impl BoardMask {
+    pub fn parse(mask: &str, rows: i32, cols: i32) -> Result<Self, BoggleError> {
+        let (rows, cols, cells) = split_layout(mask, rows, cols)?;
+        check_dimensions(rows, cols)?;
+        let expected = rows as usize * cols as usize;
+        if cells.len() != expected {
+            return Err(BoggleError::SizeMismatch {
+                expected,
+                found: cells.len(),
+            });
+        }
+        Ok(Self {
+            rows,
+            cols,
+            blocked: cells
+                .iter()
+                .map(|cell| cell == "#" || cell == ".")
+                .collect(),
+        })
+    }
+
+    pub fn rows(&self) -> i32 {
+        self.rows
+    }
+
+    pub fn cols(&self) -> i32 {
+        self.cols
+    }
+
+    pub fn is_blocked(&self, cell: usize) -> bool {
+        self.blocked[cell]
+    }
+}
+
+// Works out the dimensions of a board string and pulls out its cells, falling back on `rows` by
+// `cols` when the string doesn't say
+fn split_layout(board: &str, rows: i32, cols: i32) -> Result<(i32, i32, Vec<String>), BoggleError> {
+    if let Some((dimensions, letters)) = board.split_once(':') {
+        let (rows, cols) = parse_dimensions(dimensions)?;
+        return Ok((rows, cols, split_cells(letters)?));
+    }
+    if board.contains('/') {
+        let board_rows = board
+            .split('/')
+            .map(split_cells)
+            .collect::<Result<Vec<Vec<String>>, BoggleError>>()?;
+        let cols = board_rows[0].len();
+        if let Some(row) = board_rows.iter().find(|row| row.len() != cols) {
+            return Err(BoggleError::InvalidBoardFormat(format!(
+                "row '{}' should have {} letters",
+                row.concat(),
+                cols
+            )));
+        }
+        return Ok((board_rows.len() as i32, cols as i32, board_rows.concat()));
+    }
+    Ok((rows, cols, split_cells(board)?))
+}
+
+// One cell per character, except that a multi-letter face is written in brackets, e.g. "[th]"
+fn split_cells(letters: &str) -> Result<Vec<String>, BoggleError> {
+    let mut cells = vec![];
+    let mut chars = letters.chars();
+    while let Some(c) = chars.next() {
+        if c != '[' {
+            cells.push(c.to_string());
+            continue;
+        }
+        let face: String = chars.by_ref().take_while(|&c| c != ']').collect();
+        if !chars.as_str().is_empty() || letters.ends_with(']') {
+            cells.push(face);
+        } else {
+            return Err(BoggleError::InvalidBoardFormat(format!(
+                "'[{}' is missing its closing ']'",
+                face
+            )));
+        }
+    }
+    Ok(cells)
+}
+
+fn check_dimensions(rows: i32, cols: i32) -> Result<(), BoggleError> {
+    if rows < 1 {
+        return Err(BoggleError::UnsupportedSize(rows));
+    }
+    if cols < 1 {
+        return Err(BoggleError::UnsupportedSize(cols));
+    }
+    Ok(())
+}
+
+// "RxC", rows first
+pub fn parse_dimensions(dimensions: &str) -> Result<(i32, i32), BoggleError> {
+    let invalid = || {
+        BoggleError::InvalidBoardFormat(format!(
+            "expected dimensions like 4x5, found '{}'",
+            dimensions
+        ))
+    };
+    let (rows, cols) = dimensions
+        .to_ascii_lowercase()
+        .split_once('x')
+        .map(|(rows, cols)| (rows.trim().parse(), cols.trim().parse()))
+        .ok_or_else(invalid)?;
+    Ok((rows.map_err(|_| invalid())?, cols.map_err(|_| invalid())?))
 }
 
 impl fmt::Display for BoggleBoard {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        for row in &self.board {
-            writeln!(f, "{:?}", row)?;
+        match self.grid {
+            Grid::Square => {
+                for row in &self.board {
+                    let cells: Vec<String> = row.iter().map(|cell| cell.to_string()).collect();
+                    writeln!(f, "[{}]", cells.join(", "))?;
+                }
+            }
+            // Odd rows are pushed half a cell to the right so each cell sits between the two
+            // above it and the two below it
+            Grid::Hex => {
+                for (index, row) in self.board.iter().enumerate() {
+                    let indent = if index % 2 == 1 { "  " } else { "" };
+                    let cells: Vec<String> = row
+                        .iter()
+                        .map(|cell| format!("{:<4}", cell.to_string()))
+                        .collect();
+                    writeln!(f, "{}{}", indent, cells.concat().trim_end())?;
+                }
+            }
         }
         Ok(())
     }
 }
+
+// Anything the solver can be pointed at: a grid of dice or a graph board of any shape
+#[derive(Clone, Serialize)]
+#[serde(untagged)]
+pub enum Board {
+    Grid(BoggleBoard),
+    Graph(GraphBoard),
+}
+
+impl Board {
+    // Board strings starting with '{' are read as graph board JSON, anything else as a grid
+    pub fn parse(board: &str, rows: i32, cols: i32, grid: Grid) -> Result<Self, BoggleError> {
+        if board.trim_start().starts_with('{') {
+            GraphBoard::from_json(board).map(Board::Graph)
+        } else {
+            BoggleBoard::parse(board, rows, cols, grid).map(Board::Grid)
+        }
+    }
+
+    // Graph boards count as a single column, the same way they are listed when not laid out
+    pub fn dimensions(&self) -> (i32, i32) {
+        match self {
+            Board::Grid(board) => (board.rows(), board.cols()),
+            Board::Graph(board) => (board.len() as i32, 1),
+        }
+    }
+
+    pub fn graph(&self, diagonals: bool, topology: Topology) -> BoggleGraph {
+        match self {
+            Board::Grid(board) => BoggleGraph::from_board(board, diagonals, topology),
+            Board::Graph(board) => BoggleGraph::from_graph_board(board),
+        }
+    }
+
+    pub fn faces(&self) -> Vec<BoggleChar> {
+        match self {
+            Board::Grid(board) => board.get_chars().concat(),
+            Board::Graph(board) => (0..board.len())
+                .map(|cell| board.letter(cell).clone())
+                .collect(),
+        }
+    }
+
+    pub fn to_board_string(&self) -> String {
+        match self {
+            Board::Grid(board) => board.to_board_string(),
+            Board::Graph(board) => {
+                serde_json::to_string(board).expect("graph boards always serialize")
+            }
+        }
+    }
+}
+
+impl From<BoggleBoard> for Board {
+    fn from(board: BoggleBoard) -> Self {
+        Board::Grid(board)
+    }
+}
+
+impl From<GraphBoard> for Board {
+    fn from(board: GraphBoard) -> Self {
+        Board::Graph(board)
+    }
+}
+
+impl fmt::Display for Board {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Board::Grid(board) => board.fmt(f),
+            Board::Graph(board) => board.fmt(f),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rejects_bad_boards() {
+        assert!(matches!(
+            BoggleBoard::from("abc1".chars().collect(), 2),
+            Err(BoggleError::InvalidBoardChar('1'))
+        ));
+        assert!(matches!(
+            BoggleBoard::from("abcde".chars().collect(), 2),
+            Err(BoggleError::SizeMismatch {
+                expected: 4,
+                found: 5
+            })
+        ));
+        assert!(matches!(
+            BoggleBoard::new(0, &mut boggle_dice::seeded_rng(0)),
+            Err(BoggleError::UnsupportedSize(0))
+        ));
+        assert!(BoggleBoard::from("abcd".chars().collect(), 2).is_ok());
+    }
+
+    #[test]
+    fn rectangular_boards() {
+        let board = BoggleBoard::parse("2x3:abcdef", 4, 4, Grid::Square).unwrap();
+        assert_eq!((board.rows(), board.cols()), (2, 3));
+        assert_eq!(board.access((1, 0)), BoggleChar::D);
+        assert!(board.in_bounds(&(2, 1)));
+        assert!(!board.in_bounds(&(1, 2)));
+        assert_eq!(board.to_board_string(), "2x3:abcdef");
+
+        let slashes = BoggleBoard::parse("abc/def", 4, 4, Grid::Square).unwrap();
+        assert_eq!(slashes.get_chars(), board.get_chars());
+        assert_eq!(
+            BoggleBoard::parse("abcdef", 3, 2, Grid::Square)
+                .unwrap()
+                .to_board_string(),
+            "3x2:abcdef"
+        );
+        assert!(matches!(
+            BoggleBoard::parse("ab/c", 4, 4, Grid::Square),
+            Err(BoggleError::InvalidBoardFormat(_))
+        ));
+        assert!(matches!(
+            BoggleBoard::parse("2by3:abcdef", 4, 4, Grid::Square),
+            Err(BoggleError::InvalidBoardFormat(_))
+        ));
+
+        let rolled = BoggleBoard::new_rect(3, 8, &mut boggle_dice::seeded_rng(1)).unwrap();
+        assert_eq!(rolled.get_chars().len(), 3);
+        assert!(rolled.get_chars().iter().all(|row| row.len() == 8));
+    }
+
+    #[test]
+    fn blocked_cells() {
+        let board = BoggleBoard::parse("a#c/.ef", 4, 4, Grid::Square).unwrap();
+        assert_eq!(board.access((0, 1)), BoggleChar::Blocked);
+        assert_eq!(board.access((1, 0)), BoggleChar::Blocked);
+        assert_eq!(board.to_board_string(), "2x3:a#c#ef");
+        assert_eq!(board.to_string(), "[A, #, C]\n[#, E, F]\n");
+
+        let mask = BoardMask::parse("#??#/????/#??#", 4, 4).unwrap();
+        let rolled = BoggleBoard::new_rect(3, 4, &mut boggle_dice::seeded_rng(1))
+            .unwrap()
+            .with_mask(&mask)
+            .unwrap();
+        let blocked: Vec<bool> = rolled
+            .get_chars()
+            .iter()
+            .flatten()
+            .map(|cell| *cell == BoggleChar::Blocked)
+            .collect();
+        assert_eq!(blocked, mask.blocked);
+        assert!(matches!(
+            BoggleBoard::new(4, &mut boggle_dice::seeded_rng(1))
+                .unwrap()
+                .with_mask(&mask),
+            Err(BoggleError::SizeMismatch { .. })
+        ));
+    }
+
+    #[test]
+    fn multi_letter_faces() {
+        let board = BoggleBoard::parse("[th]q/[in]e", 4, 4, Grid::Square).unwrap();
+        assert_eq!(board.access((0, 0)), BoggleChar::multi("th"));
+        assert_eq!(board.access((0, 1)), BoggleChar::Qu);
+        assert_eq!(board.access((1, 0)).to_char_vec(), vec!['i', 'n']);
+        assert_eq!(board.to_board_string(), "[th]q[in]e");
+        assert_eq!(board.to_string(), "[Th, Qu]\n[In, E]\n");
+        assert_eq!(
+            BoggleBoard::parse("[QU]b[Er]d", 2, 2, Grid::Square)
+                .unwrap()
+                .get_chars()[0][0],
+            BoggleChar::Qu
+        );
+
+        for bad in ["[th", "[]abc", "[t1]abc"] {
+            assert!(matches!(
+                BoggleBoard::parse(bad, 2, 2, Grid::Square),
+                Err(BoggleError::InvalidBoardFormat(_))
+            ));
+        }
+    }
+
+    #[test]
+    fn hex_boards() {
+        let board = BoggleBoard::parse("hex:abc/dqf", 4, 4, Grid::Square).unwrap();
+        assert_eq!(board.grid(), Grid::Hex);
+        assert_eq!(board.to_board_string(), "hex:2x3:abcdqf");
+        assert_eq!(board.to_string(), "A   B   C\n  D   Qu  F\n");
+        assert_eq!(
+            BoggleBoard::parse("abcdqf", 2, 3, Grid::Hex)
+                .unwrap()
+                .grid(),
+            Grid::Hex
+        );
+
+        let rolled = BoggleBoard::new_grid(5, 5, Grid::Hex, &mut boggle_dice::seeded_rng(3));
+        let reparsed = BoggleBoard::parse(
+            &rolled.as_ref().unwrap().to_board_string(),
+            4,
+            4,
+            Grid::Square,
+        )
+        .unwrap();
+        assert_eq!(reparsed.get_chars(), rolled.unwrap().get_chars());
+        assert_eq!(reparsed.grid(), Grid::Hex);
+    }
+
+    #[test]
+    fn seeded_boards_repeat() {
+        for size in [4, 5, 7] {
+            let first = BoggleBoard::new(size, &mut boggle_dice::seeded_rng(42)).unwrap();
+            let second = BoggleBoard::new(size, &mut boggle_dice::seeded_rng(42)).unwrap();
+            assert_eq!(first.get_chars(), second.get_chars());
+        }
+    }
+}
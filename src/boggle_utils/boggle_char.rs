@@ -1,7 +1,14 @@
-use serde::Serialize;
+use serde::{Serialize, Serializer};
+use std::borrow::Cow;
 use std::fmt;
+use std::str::FromStr;
 
-#[derive(Debug, PartialEq, Eq, Hash, Clone, Serialize)]
+use crate::error::BoggleError;
+
+// How a wildcard face shows up in a board string and in the letters the solver walks
+pub const WILDCARD: char = '?';
+
+#[derive(Debug, PartialEq, Eq, Hash, Clone)]
 pub enum BoggleChar {
     A,
     B,
@@ -29,9 +36,19 @@ pub enum BoggleChar {
     X,
     Y,
     Z,
+    // A hole in the board that no word can pass through
+    Blocked,
+    // A face with several letters on it, such as Th or In, stored in lowercase
+    Multi(Cow<'static, str>),
+    // A blank face that can stand for any one letter
+    Wildcard,
 }
 
 impl BoggleChar {
+    pub const fn multi(face: &'static str) -> Self {
+        BoggleChar::Multi(Cow::Borrowed(face))
+    }
+
     pub fn append_to(&self, string: &mut String) {
         for c in self.to_char_vec() {
             string.push(c);
@@ -40,32 +57,35 @@ impl BoggleChar {
 
     pub fn to_char_vec(&self) -> Vec<char> {
         match self {
-            BoggleChar::A => return vec!['a'],
-            BoggleChar::B => return vec!['b'],
-            BoggleChar::C => return vec!['c'],
-            BoggleChar::D => return vec!['d'],
-            BoggleChar::E => return vec!['e'],
-            BoggleChar::F => return vec!['f'],
-            BoggleChar::G => return vec!['g'],
-            BoggleChar::H => return vec!['h'],
-            BoggleChar::I => return vec!['i'],
-            BoggleChar::J => return vec!['j'],
-            BoggleChar::K => return vec!['k'],
-            BoggleChar::L => return vec!['l'],
-            BoggleChar::M => return vec!['m'],
-            BoggleChar::N => return vec!['n'],
-            BoggleChar::O => return vec!['o'],
-            BoggleChar::P => return vec!['p'],
-            BoggleChar::Qu => return vec!['q', 'u'],
-            BoggleChar::R => return vec!['r'],
-            BoggleChar::S => return vec!['s'],
-            BoggleChar::T => return vec!['t'],
-            BoggleChar::U => return vec!['u'],
-            BoggleChar::V => return vec!['v'],
-            BoggleChar::W => return vec!['w'],
-            BoggleChar::X => return vec!['x'],
-            BoggleChar::Y => return vec!['y'],
-            BoggleChar::Z => return vec!['z'],
+            BoggleChar::A => vec!['a'],
+            BoggleChar::B => vec!['b'],
+            BoggleChar::C => vec!['c'],
+            BoggleChar::D => vec!['d'],
+            BoggleChar::E => vec!['e'],
+            BoggleChar::F => vec!['f'],
+            BoggleChar::G => vec!['g'],
+            BoggleChar::H => vec!['h'],
+            BoggleChar::I => vec!['i'],
+            BoggleChar::J => vec!['j'],
+            BoggleChar::K => vec!['k'],
+            BoggleChar::L => vec!['l'],
+            BoggleChar::M => vec!['m'],
+            BoggleChar::N => vec!['n'],
+            BoggleChar::O => vec!['o'],
+            BoggleChar::P => vec!['p'],
+            BoggleChar::Qu => vec!['q', 'u'],
+            BoggleChar::R => vec!['r'],
+            BoggleChar::S => vec!['s'],
+            BoggleChar::T => vec!['t'],
+            BoggleChar::U => vec!['u'],
+            BoggleChar::V => vec!['v'],
+            BoggleChar::W => vec!['w'],
+            BoggleChar::X => vec!['x'],
+            BoggleChar::Y => vec!['y'],
+            BoggleChar::Z => vec!['z'],
+            BoggleChar::Blocked => vec![],
+            BoggleChar::Multi(face) => face.chars().collect(),
+            BoggleChar::Wildcard => vec![WILDCARD],
         }
     }
 }
@@ -99,13 +119,35 @@ impl fmt::Display for BoggleChar {
             BoggleChar::X => write!(f, "X"),
             BoggleChar::Y => write!(f, "Y"),
             BoggleChar::Z => write!(f, "Z"),
+            BoggleChar::Blocked => write!(f, "#"),
+            BoggleChar::Multi(face) => {
+                let mut letters = face.chars();
+                if let Some(first) = letters.next() {
+                    write!(f, "{}{}", first.to_ascii_uppercase(), letters.as_str())?;
+                }
+                Ok(())
+            }
+            BoggleChar::Wildcard => write!(f, "{}", WILDCARD),
+        }
+    }
+}
+
+// Faces go into JSON the way they read on the die in lowercase, e.g. "a", "qu", "th", "?" and "#",
+// rather than as enum variant names
+impl Serialize for BoggleChar {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            BoggleChar::Blocked => serializer.serialize_str("#"),
+            face => serializer.serialize_str(&face.to_char_vec().into_iter().collect::<String>()),
         }
     }
 }
 
-impl From<u8> for BoggleChar {
-    fn from(c: u8) -> Self {
-        match c as char {
+impl TryFrom<char> for BoggleChar {
+    type Error = BoggleError;
+
+    fn try_from(c: char) -> Result<Self, Self::Error> {
+        let letter = match c.to_ascii_uppercase() {
             'A' => BoggleChar::A,
             'B' => BoggleChar::B,
             'C' => BoggleChar::C,
@@ -132,7 +174,54 @@ impl From<u8> for BoggleChar {
             'X' => BoggleChar::X,
             'Y' => BoggleChar::Y,
             'Z' => BoggleChar::Z,
-            _ => panic!("Invalid character"), // Handle invalid characters
+            '#' | '.' => BoggleChar::Blocked,
+            WILDCARD | '*' => BoggleChar::Wildcard,
+            _ => return Err(BoggleError::InvalidBoardChar(c)),
+        };
+        Ok(letter)
+    }
+}
+
+// Reads one face of a board string: a single character as for `TryFrom<char>`, or the letters
+// between a pair of brackets, e.g. "th" from "[th]"
+impl FromStr for BoggleChar {
+    type Err = BoggleError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut chars = s.chars();
+        if let (Some(c), None) = (chars.next(), chars.next()) {
+            return BoggleChar::try_from(c);
+        }
+        let face = s.to_ascii_lowercase();
+        if face == "qu" {
+            Ok(BoggleChar::Qu)
+        } else if !face.is_empty() && face.chars().all(|c| c.is_ascii_lowercase()) {
+            Ok(BoggleChar::Multi(Cow::Owned(face)))
+        } else {
+            Err(BoggleError::InvalidBoardFormat(format!(
+                "'[{}]' is not a valid face",
+                s
+            )))
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn serializes_as_board_faces() {
+        let faces = [
+            BoggleChar::A,
+            BoggleChar::Qu,
+            BoggleChar::multi("th"),
+            BoggleChar::Wildcard,
+            BoggleChar::Blocked,
+        ];
+        assert_eq!(
+            serde_json::to_string(&faces).unwrap(),
+            r##"["a","qu","th","?","#"]"##
+        );
+    }
+}
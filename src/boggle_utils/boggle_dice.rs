@@ -1,133 +1,311 @@
-use rand::{seq::SliceRandom, Rng};
+use rand::distributions::{Distribution, WeightedIndex};
+use rand::{seq::SliceRandom, Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+use serde::Deserialize;
+use std::borrow::Cow;
+use std::fs;
+use std::path::Path;
 
 use super::boggle_char::BoggleChar;
+use crate::error::BoggleError;
 
-#[derive(Clone)]
-struct BoggleDie {
+#[derive(Clone, Debug)]
+pub struct BoggleDie {
     letters: [BoggleChar; 6],
 }
 
 impl BoggleDie {
-    fn roll(&self) -> BoggleChar {
-        let mut rand = rand::thread_rng();
+    fn roll<R: Rng + ?Sized>(&self, rand: &mut R) -> BoggleChar {
         self.letters[rand.gen_range(0..6)].clone()
     }
+
+    pub fn faces(&self) -> &[BoggleChar; 6] {
+        &self.letters
+    }
 }
 
-pub fn scramble_dice(board_size: i32) -> Vec<BoggleChar> {
-    // If 4 or 5, use the official dice
-    let mut rand = rand::thread_rng();
-    match board_size {
-        4 => {
-            let mut dice: Vec<BoggleDie> = vec![];
-            let mut indices: Vec<i32> = (0..16).collect();
-            indices.shuffle(&mut rand);
-            for index in indices {
-                dice.push(STANDARD_DICE_4X4[index as usize].clone());
-            }
-            let mut chars: Vec<BoggleChar> = vec![];
-            for die in dice {
-                chars.push(die.roll());
-            }
-            return chars;
+// The built in dice sets, by the names `DiceSet::load` knows them by. The 2008 UK and US 4x4
+// editions aren't built in yet: their faces haven't been checked against a physical set, so until
+// they are those editions have to be loaded from a dice file.
+pub const BUILTIN_DICE_SETS: [&str; 4] = ["1976", "1987", "big", "super-big"];
+
+// A physical set of dice, one per cell, that boards are rolled from. Besides the built in
+// editions, sets can be read from JSON files listing each die's six faces as written in a board
+// string, e.g. {"name": "travel", "dice": [["a", "a", "e", "e", "g", "n"], ["qu", "th", ...]]}.
+#[derive(Clone, Debug)]
+pub struct DiceSet {
+    name: Cow<'static, str>,
+    dice: Cow<'static, [BoggleDie]>,
+}
+
+#[derive(Deserialize)]
+struct DiceSetFile {
+    #[serde(default)]
+    name: Option<String>,
+    dice: Vec<Vec<String>>,
+}
+
+impl DiceSet {
+    pub fn builtin(name: &str) -> Option<Self> {
+        let dice: &'static [BoggleDie] = match name.to_ascii_lowercase().as_str() {
+            "1976" => &STANDARD_DICE_4X4,
+            "1987" => &NEW_DICE_4X4,
+            "big" => &STANDARD_DICE_5X5,
+            "super-big" => &SUPER_BIG_DICE_6X6,
+            _ => return None,
+        };
+        Some(Self {
+            name: Cow::Owned(name.to_ascii_lowercase()),
+            dice: Cow::Borrowed(dice),
+        })
+    }
+
+    // The set `scramble_dice` rolls for a board of this many cells, if it uses real dice
+    pub fn official(cells: usize) -> Option<Self> {
+        match cells {
+            16 => Self::builtin("1976"),
+            25 => Self::builtin("big"),
+            36 => Self::builtin("super-big"),
+            _ => None,
         }
-        5 => {
-            let mut dice: Vec<BoggleDie> = vec![];
-            let mut indices: Vec<i32> = (0..25).collect();
-            indices.shuffle(&mut rand);
-            for index in indices {
-                dice.push(STANDARD_DICE_5X5[index as usize].clone());
-            }
-            let mut chars: Vec<BoggleChar> = vec![];
-            for die in dice {
-                chars.push(die.roll());
-            }
-            return chars;
+    }
+
+    // A built in set by name, otherwise a dice file at that path
+    pub fn load(name: &str) -> Result<Self, BoggleError> {
+        if let Some(dice) = Self::builtin(name) {
+            return Ok(dice);
+        }
+        let json = fs::read_to_string(name).map_err(|e| {
+            BoggleError::InvalidDice(format!(
+                "'{}' is neither a built in set ({}) nor a readable file: {}",
+                name,
+                BUILTIN_DICE_SETS.join(", "),
+                e
+            ))
+        })?;
+        let stem = Path::new(name).file_stem().map_or_else(
+            || name.to_string(),
+            |stem| stem.to_string_lossy().into_owned(),
+        );
+        Self::from_json(&json, &stem)
+    }
+
+    // Files without a name of their own go by `default_name`
+    pub fn from_json(json: &str, default_name: &str) -> Result<Self, BoggleError> {
+        let file: DiceSetFile =
+            serde_json::from_str(json).map_err(|e| BoggleError::InvalidDice(e.to_string()))?;
+        if file.dice.is_empty() {
+            return Err(BoggleError::InvalidDice(String::from(
+                "the set has no dice",
+            )));
+        }
+        let dice = file
+            .dice
+            .iter()
+            .enumerate()
+            .map(|(index, faces)| {
+                let letters: Vec<BoggleChar> = faces
+                    .iter()
+                    .map(|face| face.parse())
+                    .collect::<Result<_, BoggleError>>()
+                    .map_err(|e| BoggleError::InvalidDice(format!("die {}: {}", index, e)))?;
+                let letters = letters.try_into().map_err(|letters: Vec<BoggleChar>| {
+                    BoggleError::InvalidDice(format!(
+                        "die {} has {} faces, expected 6",
+                        index,
+                        letters.len()
+                    ))
+                })?;
+                Ok(BoggleDie { letters })
+            })
+            .collect::<Result<Vec<BoggleDie>, BoggleError>>()?;
+        Ok(Self {
+            name: Cow::Owned(file.name.unwrap_or_else(|| default_name.to_string())),
+            dice: Cow::Owned(dice),
+        })
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn len(&self) -> usize {
+        self.dice.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.dice.is_empty()
+    }
+
+    pub fn dice(&self) -> &[BoggleDie] {
+        &self.dice
+    }
+
+    // Shakes the dice into a board, which has to have exactly one cell per die
+    pub fn roll<R: Rng + ?Sized>(
+        &self,
+        cells: usize,
+        rand: &mut R,
+    ) -> Result<Vec<BoggleChar>, BoggleError> {
+        self.check_fits(cells)?;
+        Ok(roll_dice(&self.dice, rand))
+    }
+
+    // Every cell of the board needs a die of its own, with none left over
+    pub fn check_fits(&self, cells: usize) -> Result<(), BoggleError> {
+        if cells != self.dice.len() {
+            return Err(BoggleError::InvalidDice(format!(
+                "the {} set has {} dice but the board has {} cells",
+                self.name,
+                self.dice.len(),
+                cells
+            )));
         }
+        Ok(())
+    }
+}
+
+fn roll_dice<R: Rng + ?Sized>(dice: &[BoggleDie], rand: &mut R) -> Vec<BoggleChar> {
+    let mut dice: Vec<&BoggleDie> = dice.iter().collect();
+    dice.shuffle(rand);
+    dice.into_iter().map(|die| die.roll(rand)).collect()
+}
+
+// Boards are always generated from an explicit seed so that they can be reproduced later
+pub fn seeded_rng(seed: u64) -> ChaCha8Rng {
+    ChaCha8Rng::seed_from_u64(seed)
+}
+
+pub fn random_seed() -> u64 {
+    rand::thread_rng().gen()
+}
+
+pub fn scramble_dice<R: Rng + ?Sized>(cells: usize, rand: &mut R) -> Vec<BoggleChar> {
+    // If the board takes 16, 25 or 36 dice, use the official dice
+    match cells {
+        16 => roll_dice(&STANDARD_DICE_4X4, rand),
+        25 => roll_dice(&STANDARD_DICE_5X5, rand),
+        36 => roll_dice(&SUPER_BIG_DICE_6X6, rand),
         _ => {
             let mut chars: Vec<BoggleChar> = vec![];
-            for _ in 0..board_size * board_size {
-                chars.push(roll_fake_dice());
+            for _ in 0..cells {
+                chars.push(roll_fake_dice(rand));
             }
-            return chars;
+            chars
         }
     }
 }
 
-// For board sizes larger than 5x5, we just create a larger board based on standard boggle letter distributions
-fn roll_fake_dice() -> BoggleChar {
-    let mut rand = rand::thread_rng();
+// Hex cells only touch six others, so hex boards lean harder on vowels and common consonants
+// than the square dice do, and throw in the common pairs from the newer editions' dice
+const HEX_LETTER_WEIGHTS: [(BoggleChar, u32); 31] = [
+    (BoggleChar::E, 110),
+    (BoggleChar::A, 90),
+    (BoggleChar::I, 80),
+    (BoggleChar::O, 75),
+    (BoggleChar::T, 70),
+    (BoggleChar::N, 65),
+    (BoggleChar::S, 65),
+    (BoggleChar::R, 65),
+    (BoggleChar::L, 45),
+    (BoggleChar::D, 35),
+    (BoggleChar::U, 35),
+    (BoggleChar::C, 30),
+    (BoggleChar::H, 28),
+    (BoggleChar::M, 28),
+    (BoggleChar::P, 25),
+    (BoggleChar::G, 22),
+    (BoggleChar::B, 18),
+    (BoggleChar::Y, 16),
+    (BoggleChar::F, 15),
+    (BoggleChar::W, 12),
+    (BoggleChar::K, 9),
+    (BoggleChar::V, 9),
+    (BoggleChar::Qu, 3),
+    (BoggleChar::X, 3),
+    (BoggleChar::Z, 3),
+    (BoggleChar::J, 2),
+    (BoggleChar::multi("th"), 6),
+    (BoggleChar::multi("in"), 5),
+    (BoggleChar::multi("er"), 5),
+    (BoggleChar::multi("he"), 4),
+    (BoggleChar::multi("an"), 4),
+];
+
+pub fn scramble_hex_dice<R: Rng + ?Sized>(cells: usize, rand: &mut R) -> Vec<BoggleChar> {
+    let weights = WeightedIndex::new(HEX_LETTER_WEIGHTS.iter().map(|(_, weight)| weight))
+        .expect("the hex letter weights are all positive");
+    (0..cells)
+        .map(|_| HEX_LETTER_WEIGHTS[weights.sample(rand)].0.clone())
+        .collect()
+}
+
+// For any other board size, we just create a board based on standard boggle letter distributions
+fn roll_fake_dice<R: Rng + ?Sized>(rand: &mut R) -> BoggleChar {
     let chance = rand.gen_range(1..86);
     if chance <= 19 {
-        return BoggleChar::E;
+        BoggleChar::E
     } else if chance <= 32 {
-        return BoggleChar::T;
+        BoggleChar::T
     } else if chance <= 44 {
         let chance2 = rand.gen_range(1..3);
         if chance2 == 1 {
-            return BoggleChar::R;
+            BoggleChar::R
         } else {
-            return BoggleChar::A;
+            BoggleChar::A
         }
     } else if chance <= 55 {
         let chance2 = rand.gen_range(1..4);
         if chance2 == 1 {
-            return BoggleChar::I;
+            BoggleChar::I
         } else if chance2 == 2 {
-            return BoggleChar::N;
+            BoggleChar::N
         } else {
-            return BoggleChar::O;
+            BoggleChar::O
         }
     } else if chance <= 64 {
-        return BoggleChar::S;
+        BoggleChar::S
     } else if chance <= 70 {
-        return BoggleChar::D;
+        BoggleChar::D
     } else if chance <= 75 {
         let chance2 = rand.gen_range(1..4);
         if chance2 == 1 {
-            return BoggleChar::C;
+            BoggleChar::C
         } else if chance2 == 2 {
-            return BoggleChar::H;
+            BoggleChar::H
         } else {
-            return BoggleChar::L;
+            BoggleChar::L
         }
     } else if chance <= 79 {
         let chance2 = rand.gen_range(1..5);
         if chance2 == 1 {
-            return BoggleChar::F;
+            BoggleChar::F
         } else if chance2 == 2 {
-            return BoggleChar::M;
+            BoggleChar::M
         } else if chance2 == 3 {
-            return BoggleChar::U;
+            BoggleChar::U
         } else {
-            return BoggleChar::P;
+            BoggleChar::P
         }
     } else if chance <= 82 {
         let chance2 = rand.gen_range(1..3);
         if chance2 == 1 {
-            return BoggleChar::G;
+            BoggleChar::G
         } else {
-            return BoggleChar::Y;
+            BoggleChar::Y
         }
     } else if chance <= 84 {
-        return BoggleChar::W;
-    }
-    let chance2 = rand.gen_range(1..7);
-
-    if chance2 == 1 {
-        return BoggleChar::B;
-    } else if chance2 == 2 {
-        return BoggleChar::J;
-    } else if chance2 == 3 {
-        return BoggleChar::K;
-    } else if chance2 == 4 {
-        return BoggleChar::Qu;
-    } else if chance2 == 5 {
-        return BoggleChar::V;
-    } else if chance2 == 6 {
-        return BoggleChar::X;
+        BoggleChar::W
+    } else {
+        match rand.gen_range(1..7) {
+            1 => BoggleChar::B,
+            2 => BoggleChar::J,
+            3 => BoggleChar::K,
+            4 => BoggleChar::Qu,
+            5 => BoggleChar::V,
+            _ => BoggleChar::X,
+        }
     }
-    BoggleChar::Z
 }
 
 const STANDARD_DICE_4X4: [BoggleDie; 16] = [
@@ -293,6 +471,170 @@ const STANDARD_DICE_4X4: [BoggleDie; 16] = [
     },
 ];
 
+// The 4x4 dice from the 1987 redesign, which made the awkward letters rarer
+const NEW_DICE_4X4: [BoggleDie; 16] = [
+    BoggleDie {
+        letters: [
+            BoggleChar::A,
+            BoggleChar::A,
+            BoggleChar::E,
+            BoggleChar::E,
+            BoggleChar::G,
+            BoggleChar::N,
+        ],
+    },
+    BoggleDie {
+        letters: [
+            BoggleChar::A,
+            BoggleChar::B,
+            BoggleChar::B,
+            BoggleChar::J,
+            BoggleChar::O,
+            BoggleChar::O,
+        ],
+    },
+    BoggleDie {
+        letters: [
+            BoggleChar::A,
+            BoggleChar::C,
+            BoggleChar::H,
+            BoggleChar::O,
+            BoggleChar::P,
+            BoggleChar::S,
+        ],
+    },
+    BoggleDie {
+        letters: [
+            BoggleChar::A,
+            BoggleChar::F,
+            BoggleChar::F,
+            BoggleChar::K,
+            BoggleChar::P,
+            BoggleChar::S,
+        ],
+    },
+    BoggleDie {
+        letters: [
+            BoggleChar::A,
+            BoggleChar::O,
+            BoggleChar::O,
+            BoggleChar::T,
+            BoggleChar::T,
+            BoggleChar::W,
+        ],
+    },
+    BoggleDie {
+        letters: [
+            BoggleChar::C,
+            BoggleChar::I,
+            BoggleChar::M,
+            BoggleChar::O,
+            BoggleChar::T,
+            BoggleChar::U,
+        ],
+    },
+    BoggleDie {
+        letters: [
+            BoggleChar::D,
+            BoggleChar::E,
+            BoggleChar::I,
+            BoggleChar::L,
+            BoggleChar::R,
+            BoggleChar::X,
+        ],
+    },
+    BoggleDie {
+        letters: [
+            BoggleChar::D,
+            BoggleChar::E,
+            BoggleChar::L,
+            BoggleChar::R,
+            BoggleChar::V,
+            BoggleChar::Y,
+        ],
+    },
+    BoggleDie {
+        letters: [
+            BoggleChar::D,
+            BoggleChar::I,
+            BoggleChar::S,
+            BoggleChar::T,
+            BoggleChar::T,
+            BoggleChar::Y,
+        ],
+    },
+    BoggleDie {
+        letters: [
+            BoggleChar::E,
+            BoggleChar::E,
+            BoggleChar::G,
+            BoggleChar::H,
+            BoggleChar::N,
+            BoggleChar::W,
+        ],
+    },
+    BoggleDie {
+        letters: [
+            BoggleChar::E,
+            BoggleChar::E,
+            BoggleChar::I,
+            BoggleChar::N,
+            BoggleChar::S,
+            BoggleChar::U,
+        ],
+    },
+    BoggleDie {
+        letters: [
+            BoggleChar::E,
+            BoggleChar::H,
+            BoggleChar::R,
+            BoggleChar::T,
+            BoggleChar::V,
+            BoggleChar::W,
+        ],
+    },
+    BoggleDie {
+        letters: [
+            BoggleChar::E,
+            BoggleChar::I,
+            BoggleChar::O,
+            BoggleChar::S,
+            BoggleChar::S,
+            BoggleChar::T,
+        ],
+    },
+    BoggleDie {
+        letters: [
+            BoggleChar::E,
+            BoggleChar::L,
+            BoggleChar::R,
+            BoggleChar::T,
+            BoggleChar::T,
+            BoggleChar::Y,
+        ],
+    },
+    BoggleDie {
+        letters: [
+            BoggleChar::H,
+            BoggleChar::I,
+            BoggleChar::M,
+            BoggleChar::N,
+            BoggleChar::Qu,
+            BoggleChar::U,
+        ],
+    },
+    BoggleDie {
+        letters: [
+            BoggleChar::H,
+            BoggleChar::L,
+            BoggleChar::N,
+            BoggleChar::N,
+            BoggleChar::R,
+            BoggleChar::Z,
+        ],
+    },
+];
+
 const STANDARD_DICE_5X5: [BoggleDie; 25] = [
     BoggleDie {
         letters: [
@@ -545,3 +887,453 @@ const STANDARD_DICE_5X5: [BoggleDie; 25] = [
         ],
     },
 ];
+
+// Super Big Boggle, with a die of multi-letter faces and three blank faces on another. Blanks are
+// dead squares that no word can use.
+static SUPER_BIG_DICE_6X6: [BoggleDie; 36] = [
+    BoggleDie {
+        letters: [
+            BoggleChar::A,
+            BoggleChar::A,
+            BoggleChar::A,
+            BoggleChar::F,
+            BoggleChar::R,
+            BoggleChar::S,
+        ],
+    },
+    BoggleDie {
+        letters: [
+            BoggleChar::A,
+            BoggleChar::A,
+            BoggleChar::E,
+            BoggleChar::E,
+            BoggleChar::E,
+            BoggleChar::E,
+        ],
+    },
+    BoggleDie {
+        letters: [
+            BoggleChar::A,
+            BoggleChar::A,
+            BoggleChar::E,
+            BoggleChar::E,
+            BoggleChar::O,
+            BoggleChar::O,
+        ],
+    },
+    BoggleDie {
+        letters: [
+            BoggleChar::A,
+            BoggleChar::A,
+            BoggleChar::F,
+            BoggleChar::I,
+            BoggleChar::R,
+            BoggleChar::S,
+        ],
+    },
+    BoggleDie {
+        letters: [
+            BoggleChar::A,
+            BoggleChar::B,
+            BoggleChar::D,
+            BoggleChar::E,
+            BoggleChar::I,
+            BoggleChar::O,
+        ],
+    },
+    BoggleDie {
+        letters: [
+            BoggleChar::A,
+            BoggleChar::D,
+            BoggleChar::E,
+            BoggleChar::N,
+            BoggleChar::N,
+            BoggleChar::N,
+        ],
+    },
+    BoggleDie {
+        letters: [
+            BoggleChar::A,
+            BoggleChar::E,
+            BoggleChar::E,
+            BoggleChar::E,
+            BoggleChar::E,
+            BoggleChar::M,
+        ],
+    },
+    BoggleDie {
+        letters: [
+            BoggleChar::A,
+            BoggleChar::E,
+            BoggleChar::E,
+            BoggleChar::G,
+            BoggleChar::M,
+            BoggleChar::U,
+        ],
+    },
+    BoggleDie {
+        letters: [
+            BoggleChar::A,
+            BoggleChar::E,
+            BoggleChar::G,
+            BoggleChar::M,
+            BoggleChar::N,
+            BoggleChar::N,
+        ],
+    },
+    BoggleDie {
+        letters: [
+            BoggleChar::A,
+            BoggleChar::E,
+            BoggleChar::I,
+            BoggleChar::L,
+            BoggleChar::M,
+            BoggleChar::N,
+        ],
+    },
+    BoggleDie {
+        letters: [
+            BoggleChar::A,
+            BoggleChar::E,
+            BoggleChar::I,
+            BoggleChar::N,
+            BoggleChar::O,
+            BoggleChar::U,
+        ],
+    },
+    BoggleDie {
+        letters: [
+            BoggleChar::A,
+            BoggleChar::F,
+            BoggleChar::I,
+            BoggleChar::R,
+            BoggleChar::S,
+            BoggleChar::Y,
+        ],
+    },
+    BoggleDie {
+        letters: [
+            BoggleChar::multi("an"),
+            BoggleChar::multi("er"),
+            BoggleChar::multi("he"),
+            BoggleChar::multi("in"),
+            BoggleChar::Qu,
+            BoggleChar::multi("th"),
+        ],
+    },
+    BoggleDie {
+        letters: [
+            BoggleChar::B,
+            BoggleChar::B,
+            BoggleChar::J,
+            BoggleChar::K,
+            BoggleChar::X,
+            BoggleChar::Z,
+        ],
+    },
+    BoggleDie {
+        letters: [
+            BoggleChar::C,
+            BoggleChar::C,
+            BoggleChar::E,
+            BoggleChar::N,
+            BoggleChar::S,
+            BoggleChar::T,
+        ],
+    },
+    BoggleDie {
+        letters: [
+            BoggleChar::C,
+            BoggleChar::D,
+            BoggleChar::D,
+            BoggleChar::L,
+            BoggleChar::N,
+            BoggleChar::N,
+        ],
+    },
+    BoggleDie {
+        letters: [
+            BoggleChar::C,
+            BoggleChar::E,
+            BoggleChar::I,
+            BoggleChar::I,
+            BoggleChar::T,
+            BoggleChar::T,
+        ],
+    },
+    BoggleDie {
+        letters: [
+            BoggleChar::C,
+            BoggleChar::E,
+            BoggleChar::I,
+            BoggleChar::P,
+            BoggleChar::S,
+            BoggleChar::T,
+        ],
+    },
+    BoggleDie {
+        letters: [
+            BoggleChar::C,
+            BoggleChar::F,
+            BoggleChar::G,
+            BoggleChar::N,
+            BoggleChar::U,
+            BoggleChar::Y,
+        ],
+    },
+    BoggleDie {
+        letters: [
+            BoggleChar::D,
+            BoggleChar::D,
+            BoggleChar::H,
+            BoggleChar::N,
+            BoggleChar::O,
+            BoggleChar::T,
+        ],
+    },
+    BoggleDie {
+        letters: [
+            BoggleChar::D,
+            BoggleChar::H,
+            BoggleChar::H,
+            BoggleChar::L,
+            BoggleChar::O,
+            BoggleChar::R,
+        ],
+    },
+    BoggleDie {
+        letters: [
+            BoggleChar::D,
+            BoggleChar::H,
+            BoggleChar::H,
+            BoggleChar::N,
+            BoggleChar::O,
+            BoggleChar::W,
+        ],
+    },
+    BoggleDie {
+        letters: [
+            BoggleChar::D,
+            BoggleChar::H,
+            BoggleChar::L,
+            BoggleChar::N,
+            BoggleChar::O,
+            BoggleChar::R,
+        ],
+    },
+    BoggleDie {
+        letters: [
+            BoggleChar::E,
+            BoggleChar::H,
+            BoggleChar::I,
+            BoggleChar::L,
+            BoggleChar::R,
+            BoggleChar::S,
+        ],
+    },
+    BoggleDie {
+        letters: [
+            BoggleChar::E,
+            BoggleChar::I,
+            BoggleChar::I,
+            BoggleChar::L,
+            BoggleChar::S,
+            BoggleChar::T,
+        ],
+    },
+    BoggleDie {
+        letters: [
+            BoggleChar::E,
+            BoggleChar::I,
+            BoggleChar::L,
+            BoggleChar::P,
+            BoggleChar::S,
+            BoggleChar::T,
+        ],
+    },
+    BoggleDie {
+        letters: [
+            BoggleChar::E,
+            BoggleChar::I,
+            BoggleChar::O,
+            BoggleChar::Blocked,
+            BoggleChar::Blocked,
+            BoggleChar::Blocked,
+        ],
+    },
+    BoggleDie {
+        letters: [
+            BoggleChar::E,
+            BoggleChar::M,
+            BoggleChar::T,
+            BoggleChar::T,
+            BoggleChar::T,
+            BoggleChar::O,
+        ],
+    },
+    BoggleDie {
+        letters: [
+            BoggleChar::E,
+            BoggleChar::N,
+            BoggleChar::S,
+            BoggleChar::S,
+            BoggleChar::S,
+            BoggleChar::U,
+        ],
+    },
+    BoggleDie {
+        letters: [
+            BoggleChar::G,
+            BoggleChar::O,
+            BoggleChar::R,
+            BoggleChar::R,
+            BoggleChar::V,
+            BoggleChar::W,
+        ],
+    },
+    BoggleDie {
+        letters: [
+            BoggleChar::H,
+            BoggleChar::I,
+            BoggleChar::R,
+            BoggleChar::S,
+            BoggleChar::T,
+            BoggleChar::V,
+        ],
+    },
+    BoggleDie {
+        letters: [
+            BoggleChar::H,
+            BoggleChar::O,
+            BoggleChar::P,
+            BoggleChar::R,
+            BoggleChar::S,
+            BoggleChar::T,
+        ],
+    },
+    BoggleDie {
+        letters: [
+            BoggleChar::I,
+            BoggleChar::P,
+            BoggleChar::R,
+            BoggleChar::S,
+            BoggleChar::Y,
+            BoggleChar::Y,
+        ],
+    },
+    BoggleDie {
+        letters: [
+            BoggleChar::J,
+            BoggleChar::K,
+            BoggleChar::Qu,
+            BoggleChar::W,
+            BoggleChar::X,
+            BoggleChar::Z,
+        ],
+    },
+    BoggleDie {
+        letters: [
+            BoggleChar::N,
+            BoggleChar::O,
+            BoggleChar::O,
+            BoggleChar::T,
+            BoggleChar::U,
+            BoggleChar::W,
+        ],
+    },
+    BoggleDie {
+        letters: [
+            BoggleChar::O,
+            BoggleChar::O,
+            BoggleChar::O,
+            BoggleChar::T,
+            BoggleChar::T,
+            BoggleChar::U,
+        ],
+    },
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn dice_sets() {
+        // Every name the help text offers works, and nothing else does
+        for name in BUILTIN_DICE_SETS {
+            assert!(DiceSet::builtin(name).is_some());
+        }
+        assert!(DiceSet::builtin("classic").is_none());
+
+        let set = DiceSet::builtin("1987").unwrap();
+        assert_eq!(set.len(), 16);
+        assert_eq!(set.dice()[14].faces()[4], BoggleChar::Qu);
+        let rolled = set.roll(16, &mut seeded_rng(1)).unwrap();
+        assert_eq!(rolled, set.roll(16, &mut seeded_rng(1)).unwrap());
+        assert!(matches!(
+            set.roll(25, &mut seeded_rng(1)),
+            Err(BoggleError::InvalidDice(_))
+        ));
+
+        let json =
+            r#"{"dice": [["a", "b", "c", "d", "e", "f"], ["qu", "th", "?", "x", "y", "z"]]}"#;
+        let set = DiceSet::from_json(json, "pair").unwrap();
+        assert_eq!(set.name(), "pair");
+        assert_eq!(set.dice()[1].faces()[1], BoggleChar::multi("th"));
+
+        let five_faces = r#"{"dice": [["a", "b", "c", "d", "e"]]}"#;
+        let bad_face = r#"{"dice": [["a", "b", "c", "d", "e", "1"]]}"#;
+        for json in [five_faces, bad_face, r#"{"dice": []}"#, "[]"] {
+            assert!(matches!(
+                DiceSet::from_json(json, "bad"),
+                Err(BoggleError::InvalidDice(_))
+            ));
+        }
+    }
+
+    // Whether each rolled face can be put down to a different die of the set
+    fn rolled_from(faces: &[BoggleChar], dice: &[BoggleDie]) -> bool {
+        fn assign(
+            face: usize,
+            faces: &[BoggleChar],
+            dice: &[BoggleDie],
+            tried: &mut [bool],
+            owner: &mut [Option<usize>],
+        ) -> bool {
+            for die in 0..dice.len() {
+                if tried[die] || !dice[die].faces().contains(&faces[face]) {
+                    continue;
+                }
+                tried[die] = true;
+                if owner[die].is_none_or(|other| assign(other, faces, dice, tried, owner)) {
+                    owner[die] = Some(face);
+                    return true;
+                }
+            }
+            false
+        }
+        let mut owner = vec![None; dice.len()];
+        faces.len() == dice.len()
+            && (0..faces.len())
+                .all(|face| assign(face, faces, dice, &mut vec![false; dice.len()], &mut owner))
+    }
+
+    #[test]
+    fn super_big_dice() {
+        assert!(SUPER_BIG_DICE_6X6
+            .iter()
+            .flat_map(|die| die.faces())
+            .all(|face| *face != BoggleChar::Wildcard));
+        for seed in 0..20 {
+            let board = scramble_dice(36, &mut seeded_rng(seed));
+            assert!(rolled_from(&board, &SUPER_BIG_DICE_6X6));
+        }
+        // Five Qs can't come off dice with only two Qu faces between them
+        let five_qs: Vec<BoggleChar> = scramble_dice(36, &mut seeded_rng(0))
+            .into_iter()
+            .enumerate()
+            .map(|(i, face)| if i < 5 { BoggleChar::Qu } else { face })
+            .collect();
+        assert!(!rolled_from(&five_qs, &SUPER_BIG_DICE_6X6));
+    }
+}
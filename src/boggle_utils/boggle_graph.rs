@@ -0,0 +1,352 @@
+use super::boggle_board::BoggleBoard;
+use super::boggle_char::BoggleChar;
+use super::boggle_solution::PathStep;
+use super::graph_board::GraphBoard;
+use serde::Serialize;
+use std::fmt;
+use std::str::FromStr;
+
+// Whether the edges of the board are walls or wrap round to the opposite side. Hex boards only
+// wrap seamlessly when they have an even number of rows; with an odd number the cells along the
+// seam pick up an extra neighbour or two so that adjacency still works both ways.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Topology {
+    #[default]
+    Bounded,
+    Torus,
+}
+
+impl FromStr for Topology {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "bounded" => Ok(Topology::Bounded),
+            "torus" | "wrap" => Ok(Topology::Torus),
+            _ => Err(format!(
+                "unknown topology '{}', expected bounded or torus",
+                s
+            )),
+        }
+    }
+}
+
+impl fmt::Display for Topology {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Topology::Bounded => write!(f, "bounded"),
+            Topology::Torus => write!(f, "torus"),
+        }
+    }
+}
+
+// The board flattened into what the solver needs on its hot path: the letters each cell spells
+// and the cells next to it, both worked out once per board rather than on every step.
+#[derive(Clone, Debug)]
+pub struct BoggleGraph {
+    letters: Vec<Vec<char>>,
+    neighbours: Vec<Vec<usize>>,
+    coords: Vec<PathStep>,
+}
+
+impl BoggleGraph {
+    pub fn from_board(board: &BoggleBoard, diagonals: bool, topology: Topology) -> Self {
+        let chars = board.get_chars();
+        let rows = board.rows();
+        let cols = board.cols() as usize;
+        let cells = board.rows() as usize * cols;
+        let mut letters = Vec::with_capacity(cells);
+        let mut neighbours = Vec::with_capacity(cells);
+        let mut coords = Vec::with_capacity(cells);
+        for (row, row_chars) in chars.iter().enumerate() {
+            for (col, letter) in row_chars.iter().enumerate() {
+                letters.push(letter.to_char_vec());
+                coords.push(PathStep::new(row, col));
+                let cell = row * cols + col;
+                let mut cell_neighbours: Vec<usize> = vec![];
+                if *letter == BoggleChar::Blocked {
+                    neighbours.push(cell_neighbours);
+                    continue;
+                }
+                for step in board.grid().steps(row, diagonals) {
+                    let mut loc = (col as i32 + step.0, row as i32 + step.1);
+                    if topology == Topology::Torus {
+                        loc = (loc.0.rem_euclid(cols as i32), loc.1.rem_euclid(rows));
+                    }
+                    if !board.in_bounds(&loc) {
+                        continue;
+                    }
+                    // On a torus narrower than 3 cells both directions can wrap onto the same
+                    // cell, or back onto this one
+                    let next = loc.1 as usize * cols + loc.0 as usize;
+                    let open = chars[loc.1 as usize][loc.0 as usize] != BoggleChar::Blocked;
+                    if open && next != cell && !cell_neighbours.contains(&next) {
+                        cell_neighbours.push(next);
+                    }
+                }
+                neighbours.push(cell_neighbours);
+            }
+        }
+        // Odd-r offsets don't line up across the seam of a hex torus with an odd number of rows,
+        // so some steps only wrap one way. Words can be traced either way, so fill in the return
+        // steps.
+        if topology == Topology::Torus {
+            for cell in 0..cells {
+                for i in 0..neighbours[cell].len() {
+                    let next = neighbours[cell][i];
+                    if !neighbours[next].contains(&cell) {
+                        neighbours[next].push(cell);
+                    }
+                }
+            }
+        }
+
+        Self {
+            letters,
+            neighbours,
+            coords,
+        }
+    }
+
+    // Graph boards bring their own neighbours, so there are no diagonals or edges to worry about.
+    // As on a grid, blocked cells are cut off in both directions.
+    pub fn from_graph_board(board: &GraphBoard) -> Self {
+        let blocked = |cell: usize| *board.letter(cell) == BoggleChar::Blocked;
+        Self {
+            letters: (0..board.len())
+                .map(|cell| board.letter(cell).to_char_vec())
+                .collect(),
+            neighbours: (0..board.len())
+                .map(|cell| {
+                    if blocked(cell) {
+                        return vec![];
+                    }
+                    board
+                        .neighbours(cell)
+                        .iter()
+                        .copied()
+                        .filter(|&next| !blocked(next))
+                        .collect()
+                })
+                .collect(),
+            coords: (0..board.len()).map(|cell| board.coords(cell)).collect(),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.letters.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.letters.is_empty()
+    }
+
+    pub fn letters(&self, cell: usize) -> &[char] {
+        &self.letters[cell]
+    }
+
+    pub fn neighbours(&self, cell: usize) -> &[usize] {
+        &self.neighbours[cell]
+    }
+
+    // Blocked cells spell nothing and no word can start on or pass through them
+    pub fn is_blocked(&self, cell: usize) -> bool {
+        self.letters[cell].is_empty()
+    }
+
+    pub fn coords(&self, cell: usize) -> PathStep {
+        self.coords[cell]
+    }
+
+    pub fn cell_at(&self, step: &PathStep) -> Option<usize> {
+        self.coords
+            .iter()
+            .position(|coords| (coords.row, coords.col) == (step.row, step.col))
+    }
+
+    pub fn is_adjacent(&self, from: usize, to: usize) -> bool {
+        self.neighbours[from].contains(&to)
+    }
+}
+
+// Tracks which cells the current path has used. Boards of up to 128 cells fit in a single
+// integer, anything bigger falls back to a growable bitset.
+pub trait VisitedSet: Default {
+    fn contains(&self, cell: usize) -> bool;
+    fn insert(&mut self, cell: usize);
+    fn remove(&mut self, cell: usize);
+}
+
+impl VisitedSet for u64 {
+    fn contains(&self, cell: usize) -> bool {
+        *self & (1 << cell) != 0
+    }
+
+    fn insert(&mut self, cell: usize) {
+        *self |= 1 << cell;
+    }
+
+    fn remove(&mut self, cell: usize) {
+        *self &= !(1 << cell);
+    }
+}
+
+impl VisitedSet for u128 {
+    fn contains(&self, cell: usize) -> bool {
+        *self & (1 << cell) != 0
+    }
+
+    fn insert(&mut self, cell: usize) {
+        *self |= 1 << cell;
+    }
+
+    fn remove(&mut self, cell: usize) {
+        *self &= !(1 << cell);
+    }
+}
+
+#[derive(Default)]
+pub struct LargeVisitedSet {
+    words: Vec<u64>,
+}
+
+impl VisitedSet for LargeVisitedSet {
+    fn contains(&self, cell: usize) -> bool {
+        self.words
+            .get(cell / 64)
+            .is_some_and(|word| word & (1 << (cell % 64)) != 0)
+    }
+
+    fn insert(&mut self, cell: usize) {
+        if cell / 64 >= self.words.len() {
+            self.words.resize(cell / 64 + 1, 0);
+        }
+        self.words[cell / 64] |= 1 << (cell % 64);
+    }
+
+    fn remove(&mut self, cell: usize) {
+        if let Some(word) = self.words.get_mut(cell / 64) {
+            *word &= !(1 << (cell % 64));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::boggle_utils::boggle_board::Grid;
+
+    fn exercise<V: VisitedSet>(cells: &[usize]) {
+        let mut seen = V::default();
+        for &cell in cells {
+            assert!(!seen.contains(cell));
+            seen.insert(cell);
+            assert!(seen.contains(cell));
+        }
+        for &cell in cells {
+            seen.remove(cell);
+            assert!(!seen.contains(cell));
+        }
+    }
+
+    #[test]
+    fn visited_sets() {
+        exercise::<u64>(&[0, 5, 63]);
+        exercise::<u128>(&[0, 64, 127]);
+        exercise::<LargeVisitedSet>(&[0, 64, 200, 1000]);
+    }
+
+    #[test]
+    fn neighbour_table() {
+        let board = BoggleBoard::from("abcdefghi".chars().collect(), 3).unwrap();
+        let graph = BoggleGraph::from_board(&board, false, Topology::Bounded);
+        assert_eq!(graph.letters(4), &['e']);
+        let mut corner = graph.neighbours(0).to_vec();
+        corner.sort();
+        assert_eq!(corner, vec![1, 3]);
+        assert_eq!(graph.neighbours(4).len(), 4);
+
+        let graph = BoggleGraph::from_board(&board, true, Topology::Bounded);
+        assert_eq!(graph.neighbours(0).len(), 3);
+        assert_eq!(graph.neighbours(4).len(), 8);
+        assert!(graph.is_adjacent(0, 4));
+
+        // a b c d
+        // e f g h
+        let board = BoggleBoard::parse("abcd/efgh", 4, 4, Grid::Square).unwrap();
+        let graph = BoggleGraph::from_board(&board, false, Topology::Bounded);
+        let mut corner = graph.neighbours(3).to_vec();
+        corner.sort();
+        assert_eq!(corner, vec![2, 7]);
+        assert_eq!(graph.coords(6), PathStep::new(1, 2));
+    }
+
+    #[test]
+    fn torus_neighbours() {
+        let board = BoggleBoard::from("abcdefghi".chars().collect(), 3).unwrap();
+        let graph = BoggleGraph::from_board(&board, false, Topology::Torus);
+        let mut corner = graph.neighbours(0).to_vec();
+        corner.sort();
+        assert_eq!(corner, vec![1, 2, 3, 6]);
+        let graph = BoggleGraph::from_board(&board, true, Topology::Torus);
+        // With diagonals every cell of a 3x3 torus touches all eight others
+        assert!((0..9).all(|cell| graph.neighbours(cell).len() == 8));
+
+        // Two rows wrap onto the same cell both ways, and a single column wraps onto itself
+        let board = BoggleBoard::parse("ab/cd", 4, 4, Grid::Square).unwrap();
+        let graph = BoggleGraph::from_board(&board, false, Topology::Torus);
+        assert_eq!(graph.neighbours(0).len(), 2);
+        let board = BoggleBoard::parse("a/b/c", 4, 4, Grid::Square).unwrap();
+        let graph = BoggleGraph::from_board(&board, true, Topology::Torus);
+        assert_eq!(graph.neighbours(0).len(), 2);
+    }
+
+    #[test]
+    fn blocked_cells_are_skipped() {
+        // a # c
+        // d e .
+        let board = BoggleBoard::parse("a#c/de.", 4, 4, Grid::Square).unwrap();
+        let graph = BoggleGraph::from_board(&board, true, Topology::Bounded);
+        assert!(graph.is_blocked(1) && graph.is_blocked(5));
+        assert_eq!(graph.neighbours(1), &[] as &[usize]);
+        let mut around_e = graph.neighbours(4).to_vec();
+        around_e.sort();
+        assert_eq!(around_e, vec![0, 2, 3]);
+    }
+
+    #[test]
+    fn hex_neighbours() {
+        // a b c
+        //  d e f
+        // g h i
+        let board = BoggleBoard::parse("hex:abc/def/ghi", 4, 4, Grid::Square).unwrap();
+        let graph = BoggleGraph::from_board(&board, false, Topology::Bounded);
+        let sorted = |cell: usize| {
+            let mut neighbours = graph.neighbours(cell).to_vec();
+            neighbours.sort();
+            neighbours
+        };
+        assert_eq!(sorted(4), vec![1, 2, 3, 5, 7, 8]);
+        assert_eq!(sorted(1), vec![0, 2, 3, 4]);
+        assert_eq!(sorted(3), vec![0, 1, 4, 6, 7]);
+        // Diagonals don't add anything on a hex grid
+        let with_diagonals = BoggleGraph::from_board(&board, true, Topology::Bounded);
+        assert_eq!(with_diagonals.neighbours(4).len(), 6);
+
+        // Wrapping an odd number of rows still gives adjacency that works both ways
+        let torus = BoggleGraph::from_board(&board, false, Topology::Torus);
+        for a in 0..9 {
+            for b in 0..9 {
+                assert_eq!(
+                    torus.is_adjacent(a, b),
+                    torus.is_adjacent(b, a),
+                    "{} {}",
+                    a,
+                    b
+                );
+            }
+        }
+        assert!(torus.is_adjacent(1, 6) && torus.is_adjacent(6, 1));
+    }
+}
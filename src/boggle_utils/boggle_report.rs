@@ -0,0 +1,273 @@
+use super::boggle_board::Board;
+use super::boggle_graph::Topology;
+use super::boggle_scoring::ScoringRules;
+use super::boggle_solution::{self, PathStep};
+use serde::Serialize;
+use std::fmt;
+use std::str::FromStr;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OutputFormat {
+    Text,
+    Json,
+    Csv,
+    Ndjson,
+}
+
+impl FromStr for OutputFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "text" => Ok(OutputFormat::Text),
+            "json" => Ok(OutputFormat::Json),
+            "csv" => Ok(OutputFormat::Csv),
+            "ndjson" | "jsonl" => Ok(OutputFormat::Ndjson),
+            _ => Err(format!(
+                "unknown output format '{}', expected text, json, csv or ndjson",
+                s
+            )),
+        }
+    }
+}
+
+impl fmt::Display for OutputFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            OutputFormat::Text => write!(f, "text"),
+            OutputFormat::Json => write!(f, "json"),
+            OutputFormat::Csv => write!(f, "csv"),
+            OutputFormat::Ndjson => write!(f, "ndjson"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct WordReport {
+    pub word: String,
+    pub length: usize,
+    pub score: u32,
+    pub paths: Vec<Vec<PathStep>>,
+}
+
+// Everything a solve produced, in a form that can be handed to serde or printed in one of the
+// `OutputFormat`s. Words are ordered longest first, then alphabetically.
+#[derive(Clone, Serialize)]
+pub struct SolveReport {
+    pub board: Board,
+    pub board_string: String,
+    pub diagonals: bool,
+    pub topology: Topology,
+    pub rules: ScoringRules,
+    pub min_word_length: usize,
+    pub words: Vec<WordReport>,
+    pub word_count: usize,
+    pub max_score: u32,
+    pub elapsed_seconds: f64,
+}
+
+// The lines of an NDJSON report: the board and its settings, one line per word, then the totals
+#[derive(Serialize)]
+#[serde(tag = "type", rename_all = "lowercase")]
+enum ReportLine<'a> {
+    Board {
+        board: &'a Board,
+        board_string: &'a str,
+        diagonals: bool,
+        topology: Topology,
+        rules: &'a ScoringRules,
+        min_word_length: usize,
+    },
+    Word(&'a WordReport),
+    Summary {
+        word_count: usize,
+        max_score: u32,
+        elapsed_seconds: f64,
+    },
+}
+
+impl SolveReport {
+    pub fn to_json(&self) -> serde_json::Result<String> {
+        serde_json::to_string_pretty(self)
+    }
+
+    pub fn to_ndjson(&self) -> serde_json::Result<String> {
+        let mut lines = vec![serde_json::to_string(&ReportLine::Board {
+            board: &self.board,
+            board_string: &self.board_string,
+            diagonals: self.diagonals,
+            topology: self.topology,
+            rules: &self.rules,
+            min_word_length: self.min_word_length,
+        })?];
+        for word in &self.words {
+            lines.push(serde_json::to_string(&ReportLine::Word(word))?);
+        }
+        lines.push(serde_json::to_string(&ReportLine::Summary {
+            word_count: self.word_count,
+            max_score: self.max_score,
+            elapsed_seconds: self.elapsed_seconds,
+        })?);
+        Ok(lines.join("\n"))
+    }
+
+    // Only the word table, the board and totals don't fit into CSV rows
+    pub fn to_csv(&self) -> String {
+        let mut csv = String::from("word,length,score,paths\n");
+        for word in &self.words {
+            csv.push_str(&format!(
+                "{},{},{},{}\n",
+                csv_field(&word.word),
+                word.length,
+                word.score,
+                csv_field(&format_paths(&word.paths))
+            ));
+        }
+        csv
+    }
+
+    pub fn to_text(&self) -> String {
+        let mut text = String::new();
+        for word in &self.words {
+            text.push_str(&format!(
+                "{} {} {} {}\n",
+                word.word,
+                word.length,
+                word.score,
+                format_paths(&word.paths)
+            ));
+        }
+        text.push_str(&format!("\n{}\n", self.word_count));
+        text.push_str(&self.summary());
+        text
+    }
+
+    pub fn summary(&self) -> String {
+        format!("Max score: {} ({} rules)", self.max_score, self.rules.name)
+    }
+}
+
+// One line of a batch run. Boards that failed to parse carry the error instead of results so a
+// single bad line doesn't stop the rest of the batch.
+#[derive(Debug, Clone, Serialize)]
+pub struct BoardSummary {
+    pub line: usize,
+    pub board: String,
+    pub word_count: usize,
+    pub max_score: u32,
+    pub longest_word: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+impl BoardSummary {
+    pub const CSV_HEADER: &'static str = "line,board,word_count,max_score,longest_word,error";
+
+    pub fn failed(line: usize, board: &str, error: String) -> Self {
+        Self {
+            line,
+            board: board.to_string(),
+            word_count: 0,
+            max_score: 0,
+            longest_word: None,
+            error: Some(error),
+        }
+    }
+
+    pub fn to_csv_row(&self) -> String {
+        format!(
+            "{},{},{},{},{},{}",
+            self.line,
+            csv_field(&self.board),
+            self.word_count,
+            self.max_score,
+            csv_field(self.longest_word.as_deref().unwrap_or("")),
+            csv_field(self.error.as_deref().unwrap_or(""))
+        )
+    }
+
+    pub fn to_text(&self) -> String {
+        match &self.error {
+            Some(error) => format!("{} {} error: {}", self.line, self.board, error),
+            None => format!(
+                "{} {} {} {} {}",
+                self.line,
+                self.board,
+                self.word_count,
+                self.max_score,
+                self.longest_word.as_deref().unwrap_or("-")
+            ),
+        }
+    }
+}
+
+fn format_paths(paths: &[Vec<PathStep>]) -> String {
+    paths
+        .iter()
+        .map(|path| boggle_solution::format_path(path))
+        .collect::<Vec<String>>()
+        .join(" | ")
+}
+
+fn csv_field(field: &str) -> String {
+    if field.contains([',', '"', '\n']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::boggle_utils::boggle_board::BoggleBoard;
+
+    fn report() -> SolveReport {
+        SolveReport {
+            board: BoggleBoard::from("catq".chars().collect(), 2)
+                .unwrap()
+                .into(),
+            board_string: String::from("catq"),
+            diagonals: false,
+            topology: Topology::Bounded,
+            rules: ScoringRules::classic(),
+            min_word_length: 3,
+            words: vec![WordReport {
+                word: String::from("cat"),
+                length: 3,
+                score: 1,
+                paths: vec![vec![
+                    PathStep::new(0, 0),
+                    PathStep::new(0, 1),
+                    PathStep::new(1, 0),
+                ]],
+            }],
+            word_count: 1,
+            max_score: 1,
+            elapsed_seconds: 0.5,
+        }
+    }
+
+    #[test]
+    fn formats() {
+        let report = report();
+        assert_eq!(
+            report.to_csv(),
+            "word,length,score,paths\ncat,3,1,\"(0,0) (0,1) (1,0)\"\n"
+        );
+
+        let ndjson = report.to_ndjson().unwrap();
+        let lines: Vec<serde_json::Value> = ndjson
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        assert_eq!(lines.len(), 3);
+        assert_eq!(lines[0]["type"], "board");
+        assert_eq!(lines[1]["word"], "cat");
+        assert_eq!(lines[2]["max_score"], 1);
+
+        let json: serde_json::Value = serde_json::from_str(&report.to_json().unwrap()).unwrap();
+        assert_eq!(json["words"][0]["paths"][0][2]["row"], 1);
+        assert_eq!(json["rules"]["name"], "classic");
+    }
+}
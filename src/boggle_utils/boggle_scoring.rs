@@ -0,0 +1,133 @@
+use serde::Serialize;
+use std::fmt;
+use std::str::FromStr;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub enum ScoringPreset {
+    Classic,
+    BigBoggle,
+    SuperBigBoggle,
+}
+
+impl FromStr for ScoringPreset {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "classic" | "4x4" => Ok(ScoringPreset::Classic),
+            "big" | "big-boggle" | "5x5" => Ok(ScoringPreset::BigBoggle),
+            "super-big" | "super-big-boggle" | "6x6" => Ok(ScoringPreset::SuperBigBoggle),
+            _ => Err(format!(
+                "unknown scoring rules '{}', expected classic, big or super-big",
+                s
+            )),
+        }
+    }
+}
+
+impl fmt::Display for ScoringPreset {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ScoringPreset::Classic => write!(f, "classic"),
+            ScoringPreset::BigBoggle => write!(f, "big"),
+            ScoringPreset::SuperBigBoggle => write!(f, "super-big"),
+        }
+    }
+}
+
+// A table of points by word length. Words longer than the table either score the last entry or,
+// if `points_per_letter` is set, that many points for every letter.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ScoringRules {
+    pub name: String,
+    pub points: Vec<u32>,
+    pub points_per_letter: Option<u32>,
+}
+
+impl ScoringRules {
+    pub fn new(name: &str, points: Vec<u32>, points_per_letter: Option<u32>) -> Self {
+        Self {
+            name: name.to_string(),
+            points,
+            points_per_letter,
+        }
+    }
+
+    // Official 4x4 rules: 3-4 letters 1, 5 letters 2, 6 letters 3, 7 letters 5, 8+ letters 11
+    pub fn classic() -> Self {
+        Self::new("classic", vec![0, 0, 0, 1, 1, 2, 3, 5, 11], None)
+    }
+
+    // Big Boggle bans 3 letter words but otherwise scores like the classic game
+    pub fn big_boggle() -> Self {
+        Self::new("big", vec![0, 0, 0, 0, 1, 2, 3, 5, 11], None)
+    }
+
+    // Super Big Boggle also bans 3 letter words and scores 2 points a letter from 9 letters up
+    pub fn super_big_boggle() -> Self {
+        Self::new("super-big", vec![0, 0, 0, 0, 1, 2, 3, 5, 11], Some(2))
+    }
+
+    pub fn from_preset(preset: ScoringPreset) -> Self {
+        match preset {
+            ScoringPreset::Classic => Self::classic(),
+            ScoringPreset::BigBoggle => Self::big_boggle(),
+            ScoringPreset::SuperBigBoggle => Self::super_big_boggle(),
+        }
+    }
+
+    pub fn for_board_size(board_size: i32) -> Self {
+        Self::for_cell_count(board_size.max(0) as usize * board_size.max(0) as usize)
+    }
+
+    // Boards that aren't square get the rules of the square board with as many cells or more
+    pub fn for_cell_count(cells: usize) -> Self {
+        match cells {
+            ..=16 => Self::classic(),
+            17..=25 => Self::big_boggle(),
+            _ => Self::super_big_boggle(),
+        }
+    }
+
+    // Words are scored by their letter count, so a Qu die counts as the two letters it spells
+    pub fn score_length(&self, length: usize) -> u32 {
+        if length < self.points.len() {
+            return self.points[length];
+        }
+        match self.points_per_letter {
+            Some(per_letter) => per_letter * length as u32,
+            None => self.points.last().copied().unwrap_or(0),
+        }
+    }
+
+    pub fn score_word(&self, word: &str) -> u32 {
+        self.score_length(word.chars().count())
+    }
+}
+
+impl Default for ScoringRules {
+    fn default() -> Self {
+        Self::classic()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn preset_tables() {
+        let classic = ScoringRules::classic();
+        let scores: Vec<u32> = (3..=9).map(|len| classic.score_length(len)).collect();
+        assert_eq!(scores, vec![1, 1, 2, 3, 5, 11, 11]);
+        assert_eq!(classic.score_word("quit"), 1);
+
+        let big = ScoringRules::big_boggle();
+        assert_eq!(big.score_word("cat"), 0);
+        assert_eq!(big.score_word("cats"), 1);
+
+        let super_big = ScoringRules::super_big_boggle();
+        assert_eq!(super_big.score_length(8), 11);
+        assert_eq!(super_big.score_length(10), 20);
+    }
+}
@@ -0,0 +1,51 @@
+use serde::Serialize;
+use std::fmt;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize)]
+pub struct PathStep {
+    pub row: usize,
+    pub col: usize,
+    // The letter a wildcard die stood for in this word
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub letter: Option<char>,
+}
+
+impl PathStep {
+    pub fn new(row: usize, col: usize) -> Self {
+        Self {
+            row,
+            col,
+            letter: None,
+        }
+    }
+}
+
+impl fmt::Display for PathStep {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.letter {
+            Some(letter) => write!(f, "({},{}={})", self.row, self.col, letter),
+            None => write!(f, "({},{})", self.row, self.col),
+        }
+    }
+}
+
+// A word found on the board along with the dice used to spell it. Only the first path found is
+// kept unless the solver was asked for all of them.
+#[derive(Debug, Clone, Serialize)]
+pub struct Solution {
+    pub word: String,
+    pub paths: Vec<Vec<PathStep>>,
+}
+
+impl Solution {
+    pub fn path(&self) -> &[PathStep] {
+        &self.paths[0]
+    }
+}
+
+pub fn format_path(path: &[PathStep]) -> String {
+    path.iter()
+        .map(|step| step.to_string())
+        .collect::<Vec<String>>()
+        .join(" ")
+}
@@ -0,0 +1,236 @@
+use super::boggle_char::BoggleChar;
+use super::boggle_solution::PathStep;
+use crate::error::BoggleError;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct GraphCell {
+    pub letter: String,
+    pub neighbours: Vec<usize>,
+}
+
+// A board with any shape at all: a list of cells, each with its letter and the indices of the
+// cells next to it. The optional layout places cells on a grid for display, with nulls for gaps,
+// e.g. {"cells": [...], "layout": [[0, 1, null], [null, 2, 3]]}.
+#[derive(Debug, Clone, Serialize)]
+pub struct GraphBoard {
+    cells: Vec<GraphCell>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    layout: Option<Vec<Vec<Option<usize>>>>,
+    #[serde(skip)]
+    letters: Vec<BoggleChar>,
+}
+
+#[derive(Deserialize)]
+struct GraphBoardFile {
+    cells: Vec<GraphCell>,
+    #[serde(default)]
+    layout: Option<Vec<Vec<Option<usize>>>>,
+}
+
+impl GraphBoard {
+    pub fn new(
+        cells: Vec<GraphCell>,
+        layout: Option<Vec<Vec<Option<usize>>>>,
+    ) -> Result<Self, BoggleError> {
+        let letters = cells
+            .iter()
+            .enumerate()
+            .map(|(index, cell)| parse_letter(index, &cell.letter))
+            .collect::<Result<Vec<BoggleChar>, BoggleError>>()?;
+        let board = Self {
+            cells,
+            layout,
+            letters,
+        };
+        board.validate()?;
+        Ok(board)
+    }
+
+    pub fn from_json(json: &str) -> Result<Self, BoggleError> {
+        let file: GraphBoardFile =
+            serde_json::from_str(json).map_err(|e| BoggleError::InvalidGraph(e.to_string()))?;
+        Self::new(file.cells, file.layout)
+    }
+
+    // Adjacency has to go both ways, since a word can be traced in either direction
+    fn validate(&self) -> Result<(), BoggleError> {
+        let invalid = |message: String| Err(BoggleError::InvalidGraph(message));
+        if self.cells.is_empty() {
+            return invalid(String::from("the board has no cells"));
+        }
+        for (index, cell) in self.cells.iter().enumerate() {
+            for (position, &next) in cell.neighbours.iter().enumerate() {
+                if next >= self.cells.len() {
+                    return invalid(format!("cell {} has unknown neighbour {}", index, next));
+                }
+                if next == index {
+                    return invalid(format!("cell {} lists itself as a neighbour", index));
+                }
+                if cell.neighbours[..position].contains(&next) {
+                    return invalid(format!("cell {} lists neighbour {} twice", index, next));
+                }
+                if !self.cells[next].neighbours.contains(&index) {
+                    return invalid(format!(
+                        "cell {} lists {} as a neighbour but {} doesn't list {}",
+                        index, next, next, index
+                    ));
+                }
+            }
+        }
+
+        if let Some(layout) = &self.layout {
+            let mut placed = vec![false; self.cells.len()];
+            for &cell in layout.iter().flatten().flatten() {
+                if cell >= self.cells.len() {
+                    return invalid(format!("the layout places unknown cell {}", cell));
+                }
+                if placed[cell] {
+                    return invalid(format!("the layout places cell {} twice", cell));
+                }
+                placed[cell] = true;
+            }
+            if let Some(missing) = placed.iter().position(|placed| !placed) {
+                return invalid(format!("the layout is missing cell {}", missing));
+            }
+        }
+        Ok(())
+    }
+
+    pub fn len(&self) -> usize {
+        self.cells.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cells.is_empty()
+    }
+
+    pub fn letter(&self, cell: usize) -> &BoggleChar {
+        &self.letters[cell]
+    }
+
+    pub fn neighbours(&self, cell: usize) -> &[usize] {
+        &self.cells[cell].neighbours
+    }
+
+    // Where a cell is reported in paths: its place in the layout, or its row in the list that is
+    // shown when there is no layout
+    pub fn coords(&self, cell: usize) -> PathStep {
+        let placed = self.layout.as_ref().and_then(|layout| {
+            layout.iter().enumerate().find_map(|(row, cells)| {
+                cells
+                    .iter()
+                    .position(|&placed| placed == Some(cell))
+                    .map(|col| PathStep::new(row, col))
+            })
+        });
+        placed.unwrap_or(PathStep::new(cell, 0))
+    }
+
+    // Swaps in new letters, keeping the shape of the board
+    pub fn set_letters(&mut self, letters: Vec<BoggleChar>) {
+        for (cell, letter) in self.cells.iter_mut().zip(&letters) {
+            cell.letter = letter.to_string().to_lowercase();
+        }
+        self.letters = letters;
+    }
+}
+
+// Faces are written as on a board string, minus the brackets, so "qu" or "th" are one face
+fn parse_letter(index: usize, letter: &str) -> Result<BoggleChar, BoggleError> {
+    letter.parse().map_err(|_| {
+        BoggleError::InvalidGraph(format!(
+            "cell {} has letter '{}', expected a letter or a face such as qu or th",
+            index, letter
+        ))
+    })
+}
+
+impl fmt::Display for GraphBoard {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.layout {
+            Some(layout) => {
+                for row in layout {
+                    let cells: Vec<String> = row
+                        .iter()
+                        .map(|cell| match cell {
+                            Some(cell) => format!("{:<4}", self.letters[*cell].to_string()),
+                            None => String::from("    "),
+                        })
+                        .collect();
+                    writeln!(f, "{}", cells.concat().trim_end())?;
+                }
+            }
+            None => {
+                for (index, cell) in self.cells.iter().enumerate() {
+                    let neighbours: Vec<String> = cell
+                        .neighbours
+                        .iter()
+                        .map(|next| next.to_string())
+                        .collect();
+                    writeln!(
+                        f,
+                        "{:>3} {:<3}-> {}",
+                        index,
+                        self.letters[index].to_string(),
+                        neighbours.join(" ")
+                    )?;
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // c - a - t, with a triangle hanging off the t
+    const TRIANGLE: &str = r#"{
+        "cells": [
+            {"letter": "c", "neighbours": [1]},
+            {"letter": "a", "neighbours": [0, 2]},
+            {"letter": "t", "neighbours": [1, 3, 4]},
+            {"letter": "qu", "neighbours": [2, 4]},
+            {"letter": "s", "neighbours": [2, 3]}
+        ]
+    }"#;
+
+    #[test]
+    fn reads_and_validates_graphs() {
+        let board = GraphBoard::from_json(TRIANGLE).unwrap();
+        assert_eq!(board.len(), 5);
+        assert_eq!(board.letter(3), &BoggleChar::Qu);
+        assert_eq!(board.coords(4), PathStep::new(4, 0));
+        assert_eq!(board.to_string().lines().nth(2).unwrap(), "  2 T  -> 1 3 4");
+
+        let one_way =
+            r#"{"cells": [{"letter": "a", "neighbours": [1]}, {"letter": "b", "neighbours": []}]}"#;
+        let self_loop = r#"{"cells": [{"letter": "a", "neighbours": [0]}]}"#;
+        let bad_letter = r#"{"cells": [{"letter": "a1", "neighbours": []}]}"#;
+        let missing = r#"{"cells": [{"letter": "a", "neighbours": []}], "layout": [[null]]}"#;
+        for json in [one_way, self_loop, bad_letter, missing, "{}", "[]"] {
+            assert!(matches!(
+                GraphBoard::from_json(json),
+                Err(BoggleError::InvalidGraph(_))
+            ));
+        }
+    }
+
+    #[test]
+    fn lays_out_cells() {
+        let json = r#"{
+            "cells": [
+                {"letter": "a", "neighbours": [1]},
+                {"letter": "b", "neighbours": [0, 2]},
+                {"letter": "c", "neighbours": [1]}
+            ],
+            "layout": [[0, null], [null, 1], [2]]
+        }"#;
+        let board = GraphBoard::from_json(json).unwrap();
+        assert_eq!(board.to_string(), "A\n    B\nC\n");
+        assert_eq!(board.coords(1), PathStep::new(1, 1));
+    }
+}
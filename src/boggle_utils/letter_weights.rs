@@ -0,0 +1,121 @@
+use rand::distributions::{Distribution, WeightedIndex};
+use rand::Rng;
+
+use super::boggle_char::BoggleChar;
+use crate::utils::word_trie::WordTrie;
+
+const VOWELS: [char; 5] = ['a', 'e', 'i', 'o', 'u'];
+
+// How likely each letter is to be rolled, taken from how often it appears across the words of a
+// dictionary so that big boards stay playable whatever language the word list is in. Q always
+// comes as the Qu face.
+#[derive(Clone, Debug)]
+pub struct LetterWeights {
+    weights: [f64; 26],
+}
+
+impl LetterWeights {
+    pub fn from_dictionary<D: WordTrie>(dictionary: &D) -> Self {
+        let mut counts = [0u64; 26];
+        count_letters(dictionary, dictionary.root(), &mut counts);
+        // An empty dictionary says nothing, so fall back on every letter being as likely
+        let weights = if counts.iter().all(|&count| count == 0) {
+            [1.0; 26]
+        } else {
+            counts.map(|count| count as f64)
+        };
+        Self { weights }
+    }
+
+    // Rescales the weights so that vowels make up `share` of the letters rolled, e.g. 0.4
+    pub fn with_vowel_share(mut self, share: f64) -> Self {
+        let share = share.clamp(0.0, 1.0);
+        let vowels: f64 = VOWELS.iter().map(|&vowel| self.weights[index(vowel)]).sum();
+        let total: f64 = self.weights.iter().sum();
+        let consonants = total - vowels;
+        if vowels == 0.0 || consonants == 0.0 {
+            return self;
+        }
+        for (i, weight) in self.weights.iter_mut().enumerate() {
+            if VOWELS.contains(&letter(i)) {
+                *weight *= share / vowels;
+            } else {
+                *weight *= (1.0 - share) / consonants;
+            }
+        }
+        self
+    }
+
+    // The chance of rolling `face`, out of 1
+    pub fn chance(&self, face: &BoggleChar) -> f64 {
+        let total: f64 = self.weights.iter().sum();
+        match face.to_char_vec().first() {
+            Some(&c) if c.is_ascii_lowercase() => self.weights[index(c)] / total,
+            _ => 0.0,
+        }
+    }
+
+    pub fn roll<R: Rng + ?Sized>(&self, cells: usize, rand: &mut R) -> Vec<BoggleChar> {
+        let weights =
+            WeightedIndex::new(self.weights).expect("letter weights always have a positive total");
+        (0..cells)
+            .map(|_| {
+                BoggleChar::try_from(letter(weights.sample(rand)))
+                    .expect("a-z are all board letters")
+            })
+            .collect()
+    }
+}
+
+// Counts every letter of every word, returning how many words are below `node`. Walking the trie
+// rather than a word list means this works for any `WordTrie`.
+fn count_letters<'a, D: WordTrie>(
+    dictionary: &'a D,
+    node: D::Node<'a>,
+    counts: &mut [u64; 26],
+) -> u64 {
+    let mut words = dictionary.is_word(node) as u64;
+    for (letter, child) in dictionary.children(node) {
+        let below = count_letters(dictionary, child, counts);
+        counts[index(letter)] += below;
+        words += below;
+    }
+    words
+}
+
+fn index(letter: char) -> usize {
+    (letter as u8 - b'a') as usize
+}
+
+fn letter(index: usize) -> char {
+    char::from(b'a' + index as u8)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::boggle_utils::boggle_dice;
+    use crate::utils::dict_trie::DictTrie;
+
+    #[test]
+    fn weights_follow_the_dictionary() {
+        let mut trie = DictTrie::new();
+        for word in ["tat", "tea", "eat"] {
+            trie.insert_word(word);
+        }
+        let weights = LetterWeights::from_dictionary(&trie);
+        // t appears 4 times in the 9 letters
+        assert!((weights.chance(&BoggleChar::T) - 4.0 / 9.0).abs() < 1e-9);
+        assert_eq!(weights.chance(&BoggleChar::Z), 0.0);
+
+        let balanced = weights.with_vowel_share(0.5);
+        let vowels = balanced.chance(&BoggleChar::A) + balanced.chance(&BoggleChar::E);
+        assert!((vowels - 0.5).abs() < 1e-9);
+
+        let rolled = balanced.roll(50, &mut boggle_dice::seeded_rng(4));
+        assert_eq!(rolled, balanced.roll(50, &mut boggle_dice::seeded_rng(4)));
+        assert!(rolled
+            .iter()
+            .all(|face| [BoggleChar::T, BoggleChar::E, BoggleChar::A].contains(face)));
+    }
+}
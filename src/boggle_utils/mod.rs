@@ -1,3 +1,10 @@
+pub mod board_constraints;
 pub mod boggle_board;
 pub mod boggle_char;
 pub mod boggle_dice;
+pub mod boggle_graph;
+pub mod boggle_report;
+pub mod boggle_scoring;
+pub mod boggle_solution;
+pub mod graph_board;
+pub mod letter_weights;
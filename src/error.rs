@@ -0,0 +1,79 @@
+use std::fmt;
+use std::io;
+
+#[derive(Debug)]
+pub enum BoggleError {
+    // Reading the dictionary failed
+    DictionaryIo(io::Error),
+    // Deleting a trie cache failed. Reading and writing caches never fails a load, the trie is
+    // just rebuilt from the dictionary instead.
+    CacheIo(io::Error),
+    // The serialized trie exists but could not be decoded
+    CorruptTrieCache(String),
+    // A board string contained something that is not a letter
+    InvalidBoardChar(char),
+    // A board string's layout could not be understood, e.g. bad dimensions or ragged rows
+    InvalidBoardFormat(String),
+    // A graph board's cells, neighbours or layout don't fit together
+    InvalidGraph(String),
+    // A board string had the wrong number of letters for the board size
+    SizeMismatch { expected: usize, found: usize },
+    // A dice set could not be read, or doesn't fit the board it is rolled for
+    InvalidDice(String),
+    // The requested board size cannot be built
+    UnsupportedSize(i32),
+    // No board rolled within the allowed number of attempts met the generation constraints
+    ConstraintsUnmet(usize),
+    // Reading boards to solve, or writing out their results, failed
+    BoardIo(io::Error),
+}
+
+impl fmt::Display for BoggleError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BoggleError::DictionaryIo(e) => write!(f, "Failed to load the dictionary: {}", e),
+            BoggleError::CacheIo(e) => write!(f, "Failed to update the trie cache: {}", e),
+            BoggleError::CorruptTrieCache(e) => write!(f, "The trie cache is corrupt: {}", e),
+            BoggleError::InvalidBoardChar(c) => write!(f, "Invalid board character '{}'", c),
+            BoggleError::InvalidBoardFormat(e) => write!(f, "Invalid board layout: {}", e),
+            BoggleError::InvalidGraph(e) => write!(f, "Invalid graph board: {}", e),
+            BoggleError::SizeMismatch { expected, found } => write!(
+                f,
+                "Board size was not correct! Expected {} letters but found {}",
+                expected, found
+            ),
+            BoggleError::InvalidDice(e) => write!(f, "Invalid dice set: {}", e),
+            BoggleError::UnsupportedSize(size) => write!(f, "Unsupported board size {}", size),
+            BoggleError::ConstraintsUnmet(attempts) => write!(
+                f,
+                "No board met the constraints after {} attempts",
+                attempts
+            ),
+            BoggleError::BoardIo(e) => write!(f, "Failed to read boards or write results: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for BoggleError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            BoggleError::DictionaryIo(e) | BoggleError::CacheIo(e) | BoggleError::BoardIo(e) => {
+                Some(e)
+            }
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for BoggleError {
+    fn from(e: io::Error) -> Self {
+        BoggleError::DictionaryIo(e)
+    }
+}
+
+// Only for parsing word lists; errors writing out results go to `BoardIo` instead
+impl From<serde_json::Error> for BoggleError {
+    fn from(e: serde_json::Error) -> Self {
+        BoggleError::DictionaryIo(e.into())
+    }
+}
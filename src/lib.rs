@@ -1,232 +1,588 @@
-use boggle_utils::boggle_board::BoggleBoard;
-use boggle_utils::boggle_char::BoggleChar;
+use boggle_utils::board_constraints::BoardConstraints;
+use boggle_utils::boggle_board::{Board, BoardMask, BoggleBoard, Grid};
+use boggle_utils::boggle_char::{BoggleChar, WILDCARD};
+use boggle_utils::boggle_dice::{self, DiceSet};
+use boggle_utils::boggle_graph::{BoggleGraph, LargeVisitedSet, Topology, VisitedSet};
+use boggle_utils::boggle_report::{BoardSummary, SolveReport, WordReport};
+use boggle_utils::boggle_scoring::ScoringRules;
+use boggle_utils::boggle_solution::{PathStep, Solution};
+use boggle_utils::letter_weights::LetterWeights;
+pub use error::BoggleError;
+use rand::Rng;
 use rayon::prelude::*;
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 use std::io;
-use std::sync::mpsc;
+use std::sync::{mpsc, Arc};
 use std::thread::{self};
 use std::time::Duration;
-use utils::dict_trie::DictTrieNode;
+use utils::dict_trie::DictTrie;
+use utils::word_trie::WordTrie;
 
 pub mod utils;
 
 pub mod boggle_utils;
 
-#[derive(Clone)]
-pub struct BoggleSolver {
-    board: BoggleBoard,
+pub mod board_search;
+
+pub mod error;
+
+// Everything about how a board should be solved, shared by the solver, the game and the CLI
+#[derive(Clone, Debug)]
+pub struct SolverConfig {
+    pub rows: i32,
+    pub cols: i32,
+    pub grid: Grid,
+    pub diagonals: bool,
+    pub topology: Topology,
+    pub multi_thread: bool,
+    pub all_paths: bool,
+    pub rules: ScoringRules,
+    pub min_word_length: usize,
+    pub mask: Option<BoardMask>,
+    // Roll boards from these dice rather than the official set for the board's size
+    pub dice: Option<DiceSet>,
+    // Or roll each letter independently with these weights
+    pub letter_weights: Option<LetterWeights>,
+}
+
+impl SolverConfig {
+    pub fn new(board_size: i32) -> Self {
+        Self::rectangular(board_size, board_size)
+    }
+
+    pub fn rectangular(rows: i32, cols: i32) -> Self {
+        let cells = rows.max(0) as usize * cols.max(0) as usize;
+        Self {
+            rows,
+            cols,
+            grid: Grid::Square,
+            diagonals: false,
+            topology: Topology::Bounded,
+            multi_thread: false,
+            all_paths: false,
+            rules: ScoringRules::for_cell_count(cells),
+            min_word_length: Self::default_min_word_length(cells),
+            mask: None,
+            dice: None,
+            letter_weights: None,
+        }
+    }
+
+    // Blocks off the mask's cells on every board rolled, taking the board's shape from the mask
+    pub fn with_mask(mut self, mask: BoardMask) -> Self {
+        self.rows = mask.rows();
+        self.cols = mask.cols();
+        self.mask = Some(mask);
+        self
+    }
+
+    // A fresh random board of the configured shape
+    pub fn roll_board<R: Rng + ?Sized>(&self, rand: &mut R) -> Result<BoggleBoard, BoggleError> {
+        let board = match self.roll_letters(self.cell_count(), rand)? {
+            Some(letters) => {
+                BoggleBoard::from_faces(letters, self.rows, self.cols)?.with_grid(self.grid)
+            }
+            None => BoggleBoard::new_grid(self.rows, self.cols, self.grid, rand)?,
+        };
+        match &self.mask {
+            Some(mask) => board.with_mask(mask),
+            None => Ok(board),
+        }
+    }
+
+    // Letters from the configured dice or letter weights, when there are any
+    pub fn roll_letters<R: Rng + ?Sized>(
+        &self,
+        cells: usize,
+        rand: &mut R,
+    ) -> Result<Option<Vec<BoggleChar>>, BoggleError> {
+        match (&self.dice, &self.letter_weights) {
+            (Some(dice), _) => dice.roll(cells, rand).map(Some),
+            (None, Some(weights)) => Ok(Some(weights.roll(cells, rand))),
+            (None, None) => Ok(None),
+        }
+    }
+
+    pub fn cell_count(&self) -> usize {
+        self.rows.max(0) as usize * self.cols.max(0) as usize
+    }
+
+    // Classic Boggle accepts 3 letter words, the bigger boards need at least 4
+    pub fn default_min_word_length(cells: usize) -> usize {
+        if cells <= 16 {
+            3
+        } else {
+            4
+        }
+    }
+}
+
+// The dictionary is shared, so any number of solvers and games can run off one loaded copy
+pub struct BoggleSolver<D: WordTrie = DictTrie> {
+    board: Board,
     possible_words: HashSet<String>,
-    board_size: i32,
-    diagonals: bool,
-    dictionary: utils::dict_trie::DictTrie,
-    multi_thread: bool,
+    solutions: HashMap<String, Solution>,
+    config: SolverConfig,
+    dictionary: Arc<D>,
 }
 
-impl BoggleSolver {
-    pub fn new(
-        board_size: i32,
-        diagonals: bool,
-        dictionary_path: String,
-        multi_thread: bool,
-    ) -> Self {
+impl<D: WordTrie> Clone for BoggleSolver<D> {
+    fn clone(&self) -> Self {
+        Self {
+            board: self.board.clone(),
+            possible_words: self.possible_words.clone(),
+            solutions: self.solutions.clone(),
+            config: self.config.clone(),
+            dictionary: Arc::clone(&self.dictionary),
+        }
+    }
+}
+
+impl<D: WordTrie> BoggleSolver<D> {
+    pub fn new<R: Rng + ?Sized>(
+        config: SolverConfig,
+        dictionary: Arc<D>,
+        rand: &mut R,
+    ) -> Result<Self, BoggleError> {
         let mut boggle_board = Self {
-            board: BoggleBoard::new(board_size),
+            board: config.roll_board(rand)?.into(),
             possible_words: HashSet::new(),
-            board_size,
-            diagonals,
-            dictionary: utils::trie_manager::load_trie(dictionary_path)
-                .unwrap_or_else(|e| panic!("Failed to load or create the Trie: {}", e)),
-            multi_thread,
+            solutions: HashMap::new(),
+            config,
+            dictionary,
         };
         boggle_board.store_all_words();
-        boggle_board
+        Ok(boggle_board)
     }
 
     // To solve from a custom board
     pub fn from(
         board: Vec<char>,
-        board_size: i32,
-        diagonals: bool,
-        dictionary_path: String,
-        multi_thread: bool,
-    ) -> Self {
-        Self {
-            board: BoggleBoard::from(board, board_size),
+        config: SolverConfig,
+        dictionary: Arc<D>,
+    ) -> Result<Self, BoggleError> {
+        let mut boggle_board = Self {
+            board: BoggleBoard::from_rect(board, config.rows, config.cols)?
+                .with_grid(config.grid)
+                .into(),
             possible_words: HashSet::new(),
-            board_size,
-            diagonals,
-            dictionary: utils::trie_manager::load_trie(dictionary_path)
-                .unwrap_or_else(|e| panic!("Failed to load or create the Trie: {}", e)),
-            multi_thread,
+            solutions: HashMap::new(),
+            config,
+            dictionary,
+        };
+        boggle_board.store_all_words();
+        Ok(boggle_board)
+    }
+
+    // Solves a board that has already been built, taking its dimensions over the config's
+    pub fn from_board(
+        board: impl Into<Board>,
+        mut config: SolverConfig,
+        dictionary: Arc<D>,
+    ) -> Self {
+        let board = board.into();
+        (config.rows, config.cols) = board.dimensions();
+        if let Board::Grid(grid_board) = &board {
+            config.grid = grid_board.grid();
         }
+        let mut boggle_board = Self {
+            board,
+            possible_words: HashSet::new(),
+            solutions: HashMap::new(),
+            config,
+            dictionary,
+        };
+        boggle_board.store_all_words();
+        boggle_board
     }
 
-    pub fn get_board(&self) -> BoggleBoard {
+    pub fn get_config(&self) -> &SolverConfig {
+        &self.config
+    }
+
+    pub fn get_rules(&self) -> &ScoringRules {
+        &self.config.rules
+    }
+
+    // The score of every word on the board added together
+    pub fn max_score(&self) -> u32 {
+        self.possible_words
+            .iter()
+            .map(|word| self.config.rules.score_word(word))
+            .sum()
+    }
+
+    pub fn get_board(&self) -> Board {
         self.board.clone()
     }
 
-    pub fn get_possible_words(&self) -> HashSet<String> {
-        self.possible_words.clone()
+    pub fn get_possible_words(&self) -> &HashSet<String> {
+        &self.possible_words
+    }
+
+    pub fn contains_word(&self, word: &str) -> bool {
+        self.possible_words.contains(word)
+    }
+
+    pub fn get_dictionary(&self) -> &Arc<D> {
+        &self.dictionary
+    }
+
+    pub fn get_solutions(&self) -> &HashMap<String, Solution> {
+        &self.solutions
+    }
+
+    pub fn get_solution(&self, word: &str) -> Option<&Solution> {
+        self.solutions.get(word)
+    }
+
+    // Longest words first, ties broken alphabetically
+    pub fn sorted_words(&self) -> Vec<&String> {
+        let mut words: Vec<&String> = self.possible_words.iter().collect();
+        words.sort_by(|a, b| b.len().cmp(&a.len()).then_with(|| a.cmp(b)));
+        words
+    }
+
+    // `elapsed` is how long the solve took, which the solver leaves to the caller to measure
+    pub fn report(&self, elapsed: Duration) -> SolveReport {
+        let rules = &self.config.rules;
+        let words: Vec<WordReport> = self
+            .sorted_words()
+            .into_iter()
+            .map(|word| WordReport {
+                word: word.clone(),
+                length: word.len(),
+                score: rules.score_word(word),
+                paths: self.solutions[word].paths.clone(),
+            })
+            .collect();
+        SolveReport {
+            board: self.board.clone(),
+            board_string: self.board.to_board_string(),
+            diagonals: self.config.diagonals,
+            topology: self.config.topology,
+            rules: rules.clone(),
+            min_word_length: self.config.min_word_length,
+            word_count: words.len(),
+            max_score: self.max_score(),
+            words,
+            elapsed_seconds: elapsed.as_secs_f64(),
+        }
+    }
+
+    pub fn summary(&self, line: usize) -> BoardSummary {
+        // Sorted longest first, so the first word is the longest and alphabetically earliest
+        let longest_word = self.sorted_words().first().map(|word| word.to_string());
+        BoardSummary {
+            line,
+            board: self.board.to_board_string(),
+            word_count: self.possible_words.len(),
+            max_score: self.max_score(),
+            longest_word,
+            error: None,
+        }
+    }
+
+    // Checks that a submitted path really spells the word on this board, e.g. to verify a player
+    pub fn check_path(&self, word: &str, path: &[PathStep]) -> bool {
+        if !self.solutions.contains_key(word) {
+            return false;
+        }
+        let graph = self
+            .board
+            .graph(self.config.diagonals, self.config.topology);
+        let mut spelled: Vec<char> = Vec::new();
+        let mut seen = LargeVisitedSet::default();
+        let mut prev: Option<usize> = None;
+        for step in path {
+            let Some(cell) = graph.cell_at(step) else {
+                return false;
+            };
+            if seen.contains(cell) || prev.is_some_and(|prev| !graph.is_adjacent(prev, cell)) {
+                return false;
+            }
+            seen.insert(cell);
+            spelled.extend(graph.letters(cell));
+            prev = Some(cell);
+        }
+        // A wildcard on the path can stand for whatever letter the word has there
+        spelled.len() == word.chars().count()
+            && spelled
+                .iter()
+                .zip(word.chars())
+                .all(|(&letter, wanted)| letter == wanted || letter == WILDCARD)
     }
 
     fn store_all_words(&mut self) {
-        if self.possible_words.len() > 0 {
-            self.possible_words = HashSet::new();
+        self.possible_words = HashSet::new();
+        self.solutions = HashMap::new();
+
+        for (word, path) in self.find_all_words() {
+            self.possible_words.insert(word.clone());
+            match self.solutions.get_mut(&word) {
+                Some(solution) => {
+                    if self.config.all_paths {
+                        solution.paths.push(path);
+                    }
+                }
+                None => {
+                    self.solutions.insert(
+                        word.clone(),
+                        Solution {
+                            word,
+                            paths: vec![path],
+                        },
+                    );
+                }
+            }
         }
+    }
 
-        for word in self.find_all_words() {
-            self.possible_words.insert(word);
+    fn find_all_words(&self) -> Vec<(String, Vec<PathStep>)> {
+        let graph = self
+            .board
+            .graph(self.config.diagonals, self.config.topology);
+        // Pick the smallest visited set the board fits in
+        match graph.len() {
+            0..=64 => self.find_all_words_with::<u64>(&graph),
+            65..=128 => self.find_all_words_with::<u128>(&graph),
+            _ => self.find_all_words_with::<LargeVisitedSet>(&graph),
         }
     }
 
-    fn find_all_words(&self) -> Vec<String> {
-        let board_size = self.board_size;
-
-        let map_fn = |i: i32| -> Vec<String> {
-            let y = i / board_size;
-            let x = i % board_size;
-            let mut result = Vec::new();
-            self.step_and_search(
-                (x, y),
-                &mut HashSet::<(i32, i32)>::new(),
-                &mut result,
-                &mut vec![],
-                self.dictionary.get_start_node(),
-            );
-            result
+    fn find_all_words_with<V: VisitedSet>(
+        &self,
+        graph: &BoggleGraph,
+    ) -> Vec<(String, Vec<PathStep>)> {
+        let map_fn = |cell: usize| -> Vec<(String, Vec<PathStep>)> {
+            let mut search = SearchState::<V> {
+                graph,
+                seen: V::default(),
+                found: Vec::new(),
+                cur_word: Vec::new(),
+                cur_path: Vec::new(),
+            };
+            self.step_and_search(&mut search, cell, self.dictionary.root());
+            search.found
         };
 
-        let results: Vec<Vec<String>> = if self.multi_thread {
-            (0..board_size * board_size)
+        let open = |cell: &usize| !graph.is_blocked(*cell);
+        let results: Vec<Vec<(String, Vec<PathStep>)>> = if self.config.multi_thread {
+            (0..graph.len())
                 .into_par_iter()
+                .filter(open)
                 .map(map_fn)
                 .collect()
         } else {
-            (0..board_size * board_size)
-                .into_iter()
-                .map(map_fn)
-                .collect()
+            (0..graph.len()).filter(open).map(map_fn).collect()
         };
 
         results.into_iter().flatten().collect()
     }
 
-    fn step_and_search(
-        &self,
-        loc: (i32, i32),
-        seen: &mut HashSet<(i32, i32)>,
-        found: &mut Vec<String>,
-        cur_word: &mut Vec<char>,
-        prev_node: &DictTrieNode,
+    fn step_and_search<'a, V: VisitedSet>(
+        &'a self,
+        search: &mut SearchState<V>,
+        cell: usize,
+        prev_node: D::Node<'a>,
     ) {
-        let mut cur_node: &DictTrieNode = prev_node;
         // If we have stepped here but it was the wrong choice, return early
-        if seen.contains(&loc) {
+        if search.seen.contains(cell) {
             return;
         }
+        self.spell_die(search, cell, 0, prev_node);
+    }
 
-        let current_letter: BoggleChar = self.board.access((loc.1 as usize, loc.0 as usize));
-        match current_letter {
-            BoggleChar::Qu => {
-                if let Some(node) = cur_node.get_child(&'q') {
-                    cur_node = node;
-                } else {
-                    return;
-                }
-                cur_word.push('q');
-                if let Some(node) = cur_node.get_child(&'u') {
-                    cur_node = node;
-                } else {
-                    cur_word.pop();
-                    return;
+    // Walks the trie through every letter on the die from `from` on, so Qu, Th and the like need
+    // no special handling. A wildcard branches into every letter the trie has next.
+    fn spell_die<'a, V: VisitedSet>(
+        &'a self,
+        search: &mut SearchState<V>,
+        cell: usize,
+        from: usize,
+        prev_node: D::Node<'a>,
+    ) {
+        let mut cur_node = prev_node;
+        let letters = search.graph.letters(cell);
+        for (i, &letter) in letters.iter().enumerate().skip(from) {
+            if letter == WILDCARD {
+                for (letter, node) in self.dictionary.children(cur_node) {
+                    search.cur_word.push(letter);
+                    self.spell_die(search, cell, i + 1, node);
+                    search.cur_word.pop();
                 }
-                cur_word.push('u');
+                search.cur_word.truncate(search.cur_word.len() - (i - from));
+                return;
             }
-            _ => {
-                let letter = current_letter.to_char_vec()[0];
-                if let Some(node) = cur_node.get_child(&letter) {
-                    cur_node = node;
-                } else {
+            match self.dictionary.child(cur_node, letter) {
+                Some(node) => cur_node = node,
+                None => {
+                    search.cur_word.truncate(search.cur_word.len() - (i - from));
                     return;
                 }
-                cur_word.push(letter);
             }
+            search.cur_word.push(letter);
         }
 
-        // Check if the current location is valid
-        seen.insert(loc);
+        self.enter_cell(search, cell, cur_node);
+        search
+            .cur_word
+            .truncate(search.cur_word.len() - (letters.len() - from));
+    }
+
+    fn enter_cell<'a, V: VisitedSet>(
+        &'a self,
+        search: &mut SearchState<V>,
+        cell: usize,
+        cur_node: D::Node<'a>,
+    ) {
+        search.seen.insert(cell);
+        search.cur_path.push(cell);
 
         // If this is a valid word, put it into the seen word set
-        if cur_word.len() > 2 && cur_node.end {
-            found.push(cur_word.iter().collect::<String>());
+        if search.cur_word.len() >= self.config.min_word_length && self.dictionary.is_word(cur_node)
+        {
+            let path = search.path();
+            search
+                .found
+                .push((search.cur_word.iter().collect::<String>(), path));
         }
 
-        let steps = if self.diagonals {
-            vec![
-                (1, 0),
-                (0, 1),
-                (-1, 0),
-                (0, -1),
-                (1, 1),
-                (-1, 1),
-                (1, -1),
-                (-1, -1),
-            ]
-        } else {
-            vec![(1, 0), (0, 1), (-1, 0), (0, -1)]
-        };
+        for &next in search.graph.neighbours(cell) {
+            self.step_and_search(search, next, cur_node);
+        }
 
-        for step in steps {
-            // Take the step
-            let new_pos = (loc.0 + step.0, loc.1 + step.1);
+        search.seen.remove(cell);
+        search.cur_path.pop();
+    }
+
+    pub fn reshuffle<R: Rng + ?Sized>(&mut self, rand: &mut R) -> Result<(), BoggleError> {
+        self.board = self.roll(rand)?;
+        self.store_all_words();
+        Ok(())
+    }
 
-            // Not in bounds, skip
-            if !self.board.in_bounds(&new_pos) {
+    // Rerolls until a board meets the constraints, returning how many boards it took
+    pub fn reshuffle_until<R: Rng + ?Sized>(
+        &mut self,
+        constraints: &BoardConstraints,
+        rand: &mut R,
+    ) -> Result<usize, BoggleError> {
+        for attempt in 1..=constraints.max_attempts {
+            let board = self.roll(rand)?;
+            // Letter limits are cheap to check, so only solve the boards that pass them
+            if !constraints.allows_faces(&board.faces()) {
                 continue;
             }
-
-            self.step_and_search(new_pos, seen, found, cur_word, &cur_node);
+            self.board = board;
+            self.store_all_words();
+            if constraints.allows_summary(&self.summary(0)) {
+                return Ok(attempt);
+            }
         }
+        Err(BoggleError::ConstraintsUnmet(constraints.max_attempts))
+    }
 
-        seen.remove(&loc);
-
-        // Special handling for Qu
-        match current_letter {
-            BoggleChar::Qu => {
-                cur_word.pop();
-                cur_word.pop();
-            }
-            _ => {
-                cur_word.pop();
+    // Graph boards keep their shape and only get new letters
+    fn roll<R: Rng + ?Sized>(&self, rand: &mut R) -> Result<Board, BoggleError> {
+        match &self.board {
+            Board::Grid(_) => Ok(self.config.roll_board(rand)?.into()),
+            Board::Graph(board) => {
+                let letters = match self.config.roll_letters(board.len(), rand)? {
+                    Some(letters) => letters,
+                    None => boggle_dice::scramble_dice(board.len(), rand),
+                };
+                let mut board = board.clone();
+                board.set_letters(letters);
+                Ok(board.into())
             }
         }
     }
 
-    pub fn reshuffle(&mut self) {
-        self.board = BoggleBoard::new(self.board_size);
+    // Solves a different board with the already loaded dictionary
+    pub fn set_board(&mut self, board: BoggleBoard) -> Result<(), BoggleError> {
+        if (board.rows(), board.cols()) != (self.config.rows, self.config.cols) {
+            return Err(BoggleError::SizeMismatch {
+                expected: self.config.cell_count(),
+                found: board.rows() as usize * board.cols() as usize,
+            });
+        }
+        self.config.grid = board.grid();
+        self.board = board.into();
         self.store_all_words();
+        Ok(())
+    }
+}
+
+// Solves many boards at once, one board per rayon task, all against the same dictionary. Each
+// board comes with the line number it was read from, which is carried through to its summary.
+pub fn solve_batch<D: WordTrie>(
+    boards: &[(usize, String)],
+    config: &SolverConfig,
+    dictionary: &Arc<D>,
+) -> Vec<BoardSummary> {
+    boards
+        .par_iter()
+        .map(|(line, board)| {
+            let solver = Board::parse(board, config.rows, config.cols, config.grid).map(|parsed| {
+                BoggleSolver::from_board(parsed, config.clone(), Arc::clone(dictionary))
+            });
+            match solver {
+                Ok(solver) => solver.summary(*line),
+                Err(e) => BoardSummary::failed(*line, board, e.to_string()),
+            }
+        })
+        .collect()
+}
+
+// Everything one depth first search from a starting cell needs to carry around
+struct SearchState<'a, V: VisitedSet> {
+    graph: &'a BoggleGraph,
+    seen: V,
+    found: Vec<(String, Vec<PathStep>)>,
+    cur_word: Vec<char>,
+    cur_path: Vec<usize>,
+}
+
+impl<V: VisitedSet> SearchState<'_, V> {
+    // The coordinates of the current path, noting the letter each wildcard stood for
+    fn path(&self) -> Vec<PathStep> {
+        let mut word = self.cur_word.iter();
+        self.cur_path
+            .iter()
+            .map(|&cell| {
+                let mut step = self.graph.coords(cell);
+                for (&letter, &chosen) in self.graph.letters(cell).iter().zip(word.by_ref()) {
+                    if letter == WILDCARD {
+                        step.letter = Some(chosen);
+                    }
+                }
+                step
+            })
+            .collect()
     }
 }
 
-pub struct BoggleGame {
-    boggle: BoggleSolver,
+pub struct BoggleGame<D: WordTrie = DictTrie> {
+    boggle: BoggleSolver<D>,
     found_words: HashSet<String>,
     game_time: i32,
+    seed: u64,
 }
 
-impl BoggleGame {
+impl<D: WordTrie> BoggleGame<D> {
     pub fn new(
-        board_size: i32,
+        config: SolverConfig,
         game_time: i32,
-        diagonals: bool,
-        dictionary_path: String,
-        multi_thread: bool,
-    ) -> Self {
-        Self {
-            boggle: BoggleSolver::new(board_size, diagonals, dictionary_path.clone(), multi_thread),
+        dictionary: Arc<D>,
+        seed: u64,
+    ) -> Result<Self, BoggleError> {
+        Ok(Self {
+            boggle: BoggleSolver::new(config, dictionary, &mut boggle_dice::seeded_rng(seed))?,
             found_words: HashSet::new(),
             game_time,
-        }
+            seed,
+        })
     }
 
     pub fn start(&mut self) {
@@ -271,7 +627,11 @@ impl BoggleGame {
     }
 
     fn print_welcome_message(&self) {
+        println!("Seed: {}", self.seed);
         println!("{}", self.boggle.board);
+        if self.boggle.get_config().topology == Topology::Torus {
+            println!("The edges wrap around: words can run off one side and back on the other.");
+        }
         println!(
             "Game started! Enter as many words as you can in {} seconds.",
             self.game_time
@@ -279,14 +639,25 @@ impl BoggleGame {
     }
 
     fn process_word(&mut self, word: &str) {
-        if self.boggle.get_possible_words().contains(word) {
-            if self.found_words.contains(word) {
-                println!("You have already found this word. Try again!");
-            } else {
-                self.found_words.insert(word.to_string());
-            }
+        if let Err(message) = self.guess_word(word) {
+            println!("{}", message);
+        }
+    }
+
+    // Records a word the player found, or says why it doesn't count
+    fn guess_word(&mut self, word: &str) -> Result<(), String> {
+        let min_word_length = self.boggle.get_config().min_word_length;
+        if word.chars().count() < min_word_length {
+            Err(format!(
+                "Words must be at least {} letters long. Try again!",
+                min_word_length
+            ))
+        } else if !self.boggle.contains_word(word) {
+            Err(String::from("Not a valid word. Try again!"))
+        } else if !self.found_words.insert(word.to_string()) {
+            Err(String::from("You have already found this word. Try again!"))
         } else {
-            println!("Not a valid word. Try again!");
+            Ok(())
         }
     }
 
@@ -303,11 +674,12 @@ impl BoggleGame {
 
     fn print_found_words(&self) {
         let mut found_word_vec: Vec<&String> = self.found_words.iter().collect();
-        found_word_vec.sort_by(|a, b| b.len().cmp(&a.len()));
+        found_word_vec.sort_by_key(|word| std::cmp::Reverse(word.len()));
+        let rules = self.boggle.get_rules();
         let mut score = 0;
         for word in found_word_vec {
-            score += word.len() - 2;
-            println!("{} {}", word.to_uppercase(), word.len() - 2);
+            score += rules.score_word(word);
+            println!("{} {}", word.to_uppercase(), rules.score_word(word));
         }
         println!("\nYour final score: {}", score);
     }
@@ -318,72 +690,310 @@ impl BoggleGame {
             .possible_words
             .difference(&self.found_words)
             .collect();
-        possible_word_vec.sort_by(|a, b| b.len().cmp(&a.len()));
+        possible_word_vec.sort_by_key(|word| std::cmp::Reverse(word.len()));
+        let rules = self.boggle.get_rules();
         println!("\nYou could have found some of these words: ");
-        for i in 0..15 {
-            if i >= possible_word_vec.len() {
-                continue;
-            }
-            println!(
-                "{} {}",
-                possible_word_vec[i].to_uppercase(),
-                possible_word_vec[i].len() - 2
-            );
+        for word in possible_word_vec.iter().take(15) {
+            println!("{} {}", word.to_uppercase(), rules.score_word(word));
         }
-        let max_score: usize = possible_word_vec.iter().map(|word| word.len() - 2).sum();
+        let max_score: u32 = possible_word_vec
+            .iter()
+            .map(|word| rules.score_word(word))
+            .sum();
         println!("\nYour potential max score: {}", max_score);
     }
 }
 
-pub struct BoggleSolverInterface {
-    pub boggle: BoggleSolver,
+pub struct BoggleSolverInterface<D: WordTrie = DictTrie> {
+    pub boggle: BoggleSolver<D>,
 }
 
-impl BoggleSolverInterface {
+impl<D: WordTrie> BoggleSolverInterface<D> {
     pub fn new(
         board: String,
-        board_size: i32,
-        diagonals: bool,
-        dictionary_path: String,
-        multi_thread: bool,
-    ) -> Self {
-        if board.len() == 0 {
-            let mut solver: Self = Self {
-                boggle: BoggleSolver::new(board_size, diagonals, dictionary_path, multi_thread),
-            };
-            solver.boggle.store_all_words();
-            return solver;
+        config: SolverConfig,
+        dictionary: Arc<D>,
+        seed: u64,
+    ) -> Result<Self, BoggleError> {
+        if board.is_empty() {
+            return Ok(Self {
+                boggle: BoggleSolver::new(config, dictionary, &mut boggle_dice::seeded_rng(seed))?,
+            });
         }
-        if board.len() != board_size as usize * board_size as usize {
-            panic!("Board size was not correct!");
+        let board = Board::parse(&board, config.rows, config.cols, config.grid)?;
+        Ok(Self {
+            boggle: BoggleSolver::from_board(board, config, dictionary),
+        })
+    }
+
+    pub fn output_words(&self) {
+        println!("{}", self.boggle.report(Duration::ZERO).to_text());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use utils::compact_trie::CompactTrie;
+
+    fn dictionary(words: &[&str]) -> DictTrie {
+        let mut trie = DictTrie::new();
+        for word in words {
+            trie.insert_word(word);
         }
-        let mut solver: Self = Self {
-            boggle: BoggleSolver::from(
-                board.chars().into_iter().collect(),
-                board_size,
-                diagonals,
-                dictionary_path,
-                multi_thread,
-            ),
+        trie
+    }
+
+    // c a t
+    // s q o
+    // e u p
+    fn solve<D: WordTrie>(dictionary: Arc<D>, diagonals: bool) -> BoggleSolver<D> {
+        let mut config = SolverConfig::new(3);
+        config.diagonals = diagonals;
+        BoggleSolver::from("catsqoeup".chars().collect(), config, dictionary).unwrap()
+    }
+
+    #[test]
+    fn finds_words_with_paths() {
+        let trie = Arc::new(dictionary(&["cat", "cats", "sat", "quo", "top", "sec"]));
+        let solver = solve(Arc::clone(&trie), false);
+        let mut words: Vec<&String> = solver.get_possible_words().iter().collect();
+        words.sort();
+        assert_eq!(words, vec!["cat", "quo", "top"]);
+
+        let cat = solver.get_solution("cat").unwrap();
+        assert_eq!(
+            cat.path(),
+            &[
+                PathStep::new(0, 0),
+                PathStep::new(0, 1),
+                PathStep::new(0, 2),
+            ]
+        );
+        assert!(solver.check_path("cat", cat.path()));
+        assert!(!solver.check_path("cat", &cat.path()[..2]));
+
+        // The Qu die spells two letters from a single cell
+        assert_eq!(solver.get_solution("quo").unwrap().path().len(), 2);
+
+        // Diagonals open up "sat"
+        let solver = solve(Arc::clone(&trie), true);
+        assert!(solver.contains_word("sat"));
+        assert!(!solver.contains_word("cats"));
+        assert!(!solver.contains_word("sec"));
+
+        // Both solvers and the test share the one dictionary
+        assert_eq!(Arc::strong_count(&trie), 3);
+    }
+
+    #[test]
+    fn minimum_word_length() {
+        assert_eq!(SolverConfig::new(4).min_word_length, 3);
+        assert_eq!(SolverConfig::new(5).min_word_length, 4);
+        assert_eq!(SolverConfig::new(6).min_word_length, 4);
+
+        let trie = Arc::new(dictionary(&["at", "cat", "cats"]));
+        let board = BoggleBoard::parse("cats", 1, 4, Grid::Square).unwrap();
+        let mut config = SolverConfig::new(2);
+        config.min_word_length = 2;
+        let solver = BoggleSolver::from_board(board.clone(), config.clone(), Arc::clone(&trie));
+        assert_eq!(solver.sorted_words(), vec!["cats", "cat", "at"]);
+        config.min_word_length = 4;
+        let solver = BoggleSolver::from_board(board, config, Arc::clone(&trie));
+        assert_eq!(solver.sorted_words(), vec!["cats"]);
+
+        let mut game = BoggleGame::new(SolverConfig::new(5), 0, trie, 1).unwrap();
+        assert_eq!(
+            game.guess_word("cat"),
+            Err(String::from(
+                "Words must be at least 4 letters long. Try again!"
+            ))
+        );
+        assert!(game.found_words.is_empty());
+    }
+
+    #[test]
+    fn torus_wraps_the_edges() {
+        let trie = Arc::new(dictionary(&["cat", "act", "pet"]));
+        let solve_on = |diagonals: bool, topology: Topology| {
+            let mut config = SolverConfig::new(3);
+            config.diagonals = diagonals;
+            config.topology = topology;
+            let solver =
+                BoggleSolver::from("catsqoeup".chars().collect(), config, Arc::clone(&trie))
+                    .unwrap();
+            let mut words: Vec<String> = solver.get_possible_words().iter().cloned().collect();
+            words.sort();
+            words
         };
-        solver.boggle.store_all_words();
-        solver
+
+        assert_eq!(solve_on(false, Topology::Bounded), vec!["cat"]);
+        // c and t sit at opposite ends of the top row
+        assert_eq!(solve_on(false, Topology::Torus), vec!["act", "cat"]);
+        // e to t needs to wrap both ways at once
+        assert_eq!(solve_on(true, Topology::Bounded), vec!["cat"]);
+        assert_eq!(solve_on(true, Topology::Torus), vec!["act", "cat", "pet"]);
     }
 
-    pub fn output_words(&self) {
-        let mut possible_word_vec: Vec<String> =
-            self.boggle.get_possible_words().into_iter().collect();
-        possible_word_vec.sort_by(|a, b| {
-            let len_cmp = b.len().cmp(&a.len());
-            if len_cmp == std::cmp::Ordering::Equal {
-                a.cmp(b)
-            } else {
-                len_cmp
-            }
-        });
-        for word in &possible_word_vec {
-            println!("{} {}", word, word.len());
+    #[test]
+    fn solves_graph_boards() {
+        // c - a - t - s, with the s looping back round to the c
+        let json = r#"{"cells": [
+            {"letter": "c", "neighbours": [1, 3]},
+            {"letter": "a", "neighbours": [0, 2]},
+            {"letter": "t", "neighbours": [1, 3]},
+            {"letter": "s", "neighbours": [2, 0]}
+        ]}"#;
+        let trie = Arc::new(dictionary(&["cat", "cats", "scat", "act", "tsac"]));
+        let board = Board::parse(json, 4, 4, Grid::Square).unwrap();
+        let mut solver = BoggleSolver::from_board(board, SolverConfig::new(4), trie);
+        let mut words: Vec<&String> = solver.get_possible_words().iter().collect();
+        words.sort();
+        assert_eq!(words, vec!["cat", "cats", "scat"]);
+        assert_eq!(
+            solver.get_solution("scat").unwrap().path(),
+            &[
+                PathStep::new(3, 0),
+                PathStep::new(0, 0),
+                PathStep::new(1, 0),
+                PathStep::new(2, 0),
+            ]
+        );
+        assert!(solver.check_path("cats", solver.get_solution("cats").unwrap().path()));
+
+        // Reshuffling keeps the shape of the graph
+        solver.reshuffle(&mut boggle_dice::seeded_rng(1)).unwrap();
+        assert_eq!(solver.get_board().dimensions(), (4, 1));
+
+        // A path can't start on a blocked cell any more than it can pass through one
+        let json = r##"{"cells": [
+            {"letter": "#", "neighbours": [1]},
+            {"letter": "a", "neighbours": [0, 2]},
+            {"letter": "t", "neighbours": [1]}
+        ]}"##;
+        let board = Board::parse(json, 3, 3, Grid::Square).unwrap();
+        let trie = Arc::new(dictionary(&["at"]));
+        let mut config = SolverConfig::new(3);
+        config.min_word_length = 2;
+        let solver = BoggleSolver::from_board(board, config, trie);
+        assert!(solver.check_path("at", solver.get_solution("at").unwrap().path()));
+        let through_the_hole = [
+            PathStep::new(0, 0),
+            PathStep::new(1, 0),
+            PathStep::new(2, 0),
+        ];
+        assert!(!solver.check_path("at", &through_the_hole));
+    }
+
+    #[test]
+    fn blocked_cells_break_words() {
+        let trie = Arc::new(dictionary(&["cat", "quo", "top"]));
+        let mask = BoardMask::parse("???/?#?/???", 3, 3).unwrap();
+        let config = SolverConfig::new(3).with_mask(mask);
+        let mut solver =
+            BoggleSolver::from("catsqoeup".chars().collect(), config, Arc::clone(&trie)).unwrap();
+        assert_eq!(solver.get_possible_words().len(), 3);
+
+        // Blocking the Qu die takes out "quo"
+        let board = BoggleBoard::parse("cats#oeup", 3, 3, Grid::Square).unwrap();
+        solver.set_board(board).unwrap();
+        let mut words: Vec<&String> = solver.get_possible_words().iter().collect();
+        words.sort();
+        assert_eq!(words, vec!["cat", "top"]);
+
+        // Rolled boards always have the mask's holes
+        for seed in 0..5 {
+            solver
+                .reshuffle(&mut boggle_dice::seeded_rng(seed))
+                .unwrap();
+            let Board::Grid(board) = solver.get_board() else {
+                panic!("expected a grid board");
+            };
+            assert_eq!(board.access((1, 1)), BoggleChar::Blocked);
         }
-        println!("\n{}", possible_word_vec.len());
+    }
+
+    #[test]
+    fn multi_letter_faces() {
+        let trie = Arc::new(dictionary(&["the", "then", "thin", "tin"]));
+        let board = BoggleBoard::parse("[th]e/[in]n", 2, 2, Grid::Square).unwrap();
+        let solver = BoggleSolver::from_board(board, SolverConfig::new(2), trie);
+        let mut words: Vec<&String> = solver.get_possible_words().iter().collect();
+        words.sort();
+        // "tin" would need a lone t
+        assert_eq!(words, vec!["the", "then", "thin"]);
+    }
+
+    #[test]
+    fn wildcards_take_any_letter() {
+        let trie = Arc::new(dictionary(&["cat", "cot", "cut", "act"]));
+        let board = BoggleBoard::parse("c?t", 1, 3, Grid::Square).unwrap();
+        let solver = BoggleSolver::from_board(board, SolverConfig::new(3), trie);
+        assert_eq!(solver.sorted_words(), vec!["cat", "cot", "cut"]);
+
+        let path = solver.get_solution("cot").unwrap().path().to_vec();
+        assert_eq!(path[1].letter, Some('o'));
+        assert_eq!(path[0].letter, None);
+        assert_eq!(
+            boggle_utils::boggle_solution::format_path(&path),
+            "(0,0) (0,1=o) (0,2)"
+        );
+        assert!(solver.check_path("cut", &path));
+    }
+
+    #[test]
+    fn rerolls_until_constraints_are_met() {
+        let trie = Arc::new(dictionary(&["cat", "act", "tac", "at"]));
+        let mut config = SolverConfig::new(3);
+        let dice = [r#"["c", "a", "t", "x", "y", "z"]"#; 9].join(", ");
+        let dice = DiceSet::from_json(&format!(r#"{{"dice": [{}]}}"#, dice), "cat").unwrap();
+        config.dice = Some(dice);
+        let mut rand = boggle_dice::seeded_rng(7);
+        let mut solver = BoggleSolver::new(config, trie, &mut rand).unwrap();
+        let constraints = BoardConstraints {
+            min_words: 3,
+            max_repeats: Some(2),
+            ..BoardConstraints::default()
+        };
+        solver.reshuffle_until(&constraints, &mut rand).unwrap();
+        assert!(solver.get_possible_words().len() >= 3);
+
+        let impossible = BoardConstraints {
+            min_longest_word: 4,
+            max_attempts: 20,
+            ..BoardConstraints::default()
+        };
+        assert!(matches!(
+            solver.reshuffle_until(&impossible, &mut rand),
+            Err(BoggleError::ConstraintsUnmet(20))
+        ));
+    }
+
+    #[test]
+    fn batch_keeps_going_past_bad_boards() {
+        let trie = Arc::new(dictionary(&["cat", "quo", "top"]));
+        let boards = vec![
+            (1, String::from("catsqoeup")),
+            (2, String::from("cat")),
+            (4, String::from("xxxxxxxxx")),
+        ];
+        let summaries = solve_batch(&boards, &SolverConfig::new(3), &trie);
+        assert_eq!(summaries[0].line, 1);
+        assert_eq!(summaries[0].word_count, 3);
+        assert_eq!(summaries[0].max_score, 3);
+        assert!(summaries[1].error.is_some());
+        assert_eq!(summaries[2].word_count, 0);
+        assert_eq!(summaries[2].longest_word, None);
+    }
+
+    #[test]
+    fn compact_trie_solves_the_same() {
+        let trie = dictionary(&["cat", "sat", "quo", "top", "sec", "pout"]);
+        let compact = Arc::new(CompactTrie::from_trie(&trie));
+        let hash_map = solve(Arc::new(trie), true);
+        assert_eq!(
+            solve(compact, true).get_possible_words(),
+            hash_map.get_possible_words()
+        );
     }
 }
@@ -1,56 +1,721 @@
+use std::fs::{self, File};
+use std::io::{self, BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+use std::sync::Arc;
 use std::time::Instant;
 
-use boggle::{BoggleGame, BoggleSolverInterface};
-use clap::Parser;
+use boggle::board_search::{self, SearchOptions};
+use boggle::boggle_utils::board_constraints::BoardConstraints;
+use boggle::boggle_utils::boggle_board::{Board, BoardMask, Grid};
+use boggle::boggle_utils::boggle_dice::{self, DiceSet};
+use boggle::boggle_utils::boggle_graph::Topology;
+use boggle::boggle_utils::boggle_report::{BoardSummary, OutputFormat, SolveReport};
+use boggle::boggle_utils::boggle_scoring::{ScoringPreset, ScoringRules};
+use boggle::boggle_utils::letter_weights::LetterWeights;
+use boggle::utils::compact_trie::CompactTrie;
+use boggle::utils::dict_trie::DictTrie;
+use boggle::utils::resources::{DictionaryLocation, DEFAULT_DICTIONARY};
+use boggle::utils::word_trie::WordTrie;
+use boggle::utils::{self, trie_manager};
+use boggle::{BoggleError, BoggleGame, BoggleSolver, SolverConfig};
+use clap::{Args, Parser, Subcommand, ValueEnum};
 
 #[derive(Parser, Debug)]
 #[command(version, about, long_about)]
-struct Args {
-    #[arg(short, long, default_value_t = 90)]
-    time: i32,
+struct Cli {
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand, Debug)]
+enum Command {
+    /// Play a timed game on a random board
+    Play {
+        #[arg(short, long, default_value_t = 90)]
+        time: i32,
+
+        #[arg(long)]
+        seed: Option<u64>,
+
+        #[command(flatten)]
+        solver: SolverArgs,
+
+        #[command(flatten)]
+        dictionary: DictionaryArgs,
+    },
+    /// List every word on a given board
+    Solve {
+        #[arg(long, required_unless_present = "graph")]
+        board: Option<String>,
+
+        /// JSON file describing a graph board to solve instead of a grid
+        #[arg(long, conflicts_with = "board")]
+        graph: Option<PathBuf>,
+
+        #[arg(long, action)]
+        all_paths: bool,
+
+        /// Output format: text, json, csv or ndjson
+        #[arg(long, default_value_t = OutputFormat::Text)]
+        format: OutputFormat,
+
+        #[command(flatten)]
+        solver: SolverArgs,
+
+        #[command(flatten)]
+        dictionary: DictionaryArgs,
+    },
+    /// Solve every board in a file (or stdin), one board per line
+    Batch {
+        /// File to read boards from, reads stdin if missing or `-`
+        input: Option<PathBuf>,
+
+        /// Output format: ndjson, csv or text
+        #[arg(long, default_value_t = OutputFormat::Ndjson)]
+        format: OutputFormat,
+
+        /// How many boards to read before solving them in parallel and writing their results
+        #[arg(long, default_value_t = 1024)]
+        chunk_size: usize,
+
+        #[command(flatten)]
+        solver: SolverArgs,
+
+        #[command(flatten)]
+        dictionary: DictionaryArgs,
+    },
+    /// Roll random boards and print them as board strings
+    Generate {
+        #[command(flatten)]
+        solver: SolverArgs,
+
+        #[arg(long)]
+        seed: Option<u64>,
+
+        #[arg(short, long, default_value_t = 1)]
+        count: usize,
+
+        /// Also print each board as a grid
+        #[arg(long, action)]
+        pretty: bool,
+
+        #[command(flatten)]
+        constraints: ConstraintArgs,
+
+        // Only loaded for --letter-frequency or constraints that need the board solved
+        #[command(flatten)]
+        dictionary: DictionaryArgs,
+    },
+    /// Build, query or edit a dictionary
+    Dict {
+        #[command(subcommand)]
+        action: Option<DictAction>,
+
+        #[command(flatten)]
+        dictionary: DictionaryArgs,
+    },
+    /// Search for the highest scoring board by simulated annealing
+    Search {
+        /// Moves to try in each chain
+        #[arg(long, default_value_t = 2000)]
+        iterations: usize,
+
+        /// Independent searches to run in parallel (defaults to one per thread)
+        #[arg(long)]
+        chains: Option<usize>,
+
+        /// Starting temperature, higher explores more and 0 only ever climbs
+        #[arg(long, default_value_t = 50.0)]
+        temperature: f64,
+
+        /// Only consider boards the dice could roll: --dice, or the official set for the size
+        #[arg(long, action)]
+        real_dice: bool,
+
+        #[arg(long)]
+        seed: Option<u64>,
+
+        #[arg(long, default_value_t = OutputFormat::Text)]
+        format: OutputFormat,
+
+        #[command(flatten)]
+        solver: SolverArgs,
+
+        #[command(flatten)]
+        dictionary: DictionaryArgs,
+    },
+    /// Time the solver over a batch of random boards
+    Bench {
+        #[arg(short, long, default_value_t = 1000)]
+        boards: usize,
 
+        #[arg(long)]
+        seed: Option<u64>,
+
+        #[command(flatten)]
+        solver: SolverArgs,
+
+        #[command(flatten)]
+        dictionary: DictionaryArgs,
+    },
+}
+
+// Without an action `dict` opens the interactive dictionary prompt
+#[derive(Subcommand, Debug)]
+enum DictAction {
+    /// Rebuild the cached tries for the dictionary
+    Build,
+    /// Check whether words are in the dictionary
+    Check { words: Vec<String> },
+    /// List the dictionary words starting with a prefix
+    Extend { prefix: String },
+    /// Add words to the dictionary file
+    Insert { words: Vec<String> },
+}
+
+// Boards are `size` by `size` unless `--rows` or `--cols` say otherwise
+#[derive(Args, Debug)]
+struct BoardShape {
     #[arg(short, long, default_value_t = 4)]
     size: i32,
 
+    #[arg(long)]
+    rows: Option<i32>,
+
+    #[arg(long)]
+    cols: Option<i32>,
+
+    /// Cell shape: square, or hex for six neighbours per cell
+    #[arg(long, default_value_t = Grid::Square)]
+    grid: Grid,
+
+    /// Cells to block off on generated boards, written like a board with # or . for each hole and
+    /// ? for the open cells (e.g. "#??#/????/#??#"). Sets the board's shape if it gives its own
+    /// dimensions.
+    #[arg(long)]
+    mask: Option<String>,
+
+    /// Dice to roll boards with: a built in set (1976, 1987, big or super-big) or a JSON dice
+    /// file. Needs one die per cell.
+    #[arg(long)]
+    dice: Option<String>,
+
+    /// Roll each letter as often as it appears in the dictionary's words instead of using dice
+    #[arg(long, action, conflicts_with = "dice")]
+    letter_frequency: bool,
+
+    /// With --letter-frequency, the share of letters that should be vowels (e.g. 0.4)
+    #[arg(long, requires = "letter_frequency", value_parser = parse_share)]
+    vowels: Option<f64>,
+}
+
+impl BoardShape {
+    fn dimensions(&self) -> (i32, i32) {
+        (
+            self.rows.unwrap_or(self.size),
+            self.cols.unwrap_or(self.size),
+        )
+    }
+
+    fn mask(&self) -> Result<Option<BoardMask>, BoggleError> {
+        let (rows, cols) = self.dimensions();
+        self.mask
+            .as_deref()
+            .map(|mask| BoardMask::parse(mask, rows, cols))
+            .transpose()
+    }
+
+    fn dice(&self) -> Result<Option<DiceSet>, BoggleError> {
+        self.dice.as_deref().map(DiceSet::load).transpose()
+    }
+
+    fn letter_weights<D: WordTrie>(&self, dictionary: &D) -> Option<LetterWeights> {
+        if !self.letter_frequency {
+            return None;
+        }
+        let weights = LetterWeights::from_dictionary(dictionary);
+        Some(match self.vowels {
+            Some(share) => weights.with_vowel_share(share),
+            None => weights,
+        })
+    }
+}
+
+fn parse_share(s: &str) -> Result<f64, String> {
+    match s.parse::<f64>() {
+        Ok(share) if (0.0..=1.0).contains(&share) => Ok(share),
+        _ => Err(format!("'{}' should be a number from 0 to 1", s)),
+    }
+}
+
+#[derive(Args, Debug)]
+struct SolverArgs {
+    #[command(flatten)]
+    shape: BoardShape,
+
     #[arg(short, long, action)]
     diagonals: bool,
 
-    #[arg(long, default_value_t = String::from("dictionary.json"))]
-    dictionary: String,
+    /// Board edges: bounded, or torus to wrap round to the opposite side
+    #[arg(long, default_value_t = Topology::Bounded)]
+    topology: Topology,
 
     #[arg(short, long, action)]
     multi_thread: bool,
 
-    #[arg(long, default_value_t = String::from(""))]
-    board: String,
-}
-
-fn main() {
-    let args = Args::parse();
-    if args.board.len() == 0 {
-        let mut game = BoggleGame::new(
-            args.size,
-            args.time,
-            args.diagonals,
-            args.dictionary,
-            args.multi_thread,
-        );
-        game.start();
-    } else {
-        let start = Instant::now();
-        let solver = BoggleSolverInterface::new(
-            args.board,
-            args.size,
-            args.diagonals,
-            args.dictionary,
-            args.multi_thread,
-        );
-        let duration = start.elapsed();
-        solver.output_words();
-
-        println!(
-            "Time taken: {} seconds",
-            (duration.as_nanos() as f64) / 1e9 as f64
-        );
+    /// Scoring rules: classic, big or super-big (defaults to the board size's official rules)
+    #[arg(long)]
+    rules: Option<ScoringPreset>,
+
+    /// Shortest word that counts (defaults to 3 on 4x4 boards and 4 on bigger ones)
+    #[arg(long)]
+    min_length: Option<usize>,
+}
+
+impl SolverArgs {
+    fn to_config(&self) -> Result<SolverConfig, BoggleError> {
+        let (rows, cols) = self.shape.dimensions();
+        let mut config = self.to_config_for(rows, cols);
+        config.dice = self.shape.dice()?;
+        Ok(match self.shape.mask()? {
+            Some(mask) => config.with_mask(mask),
+            None => config,
+        })
+    }
+
+    fn to_config_for(&self, rows: i32, cols: i32) -> SolverConfig {
+        let mut config = SolverConfig::rectangular(rows, cols);
+        config.grid = self.shape.grid;
+        config.diagonals = self.diagonals;
+        config.topology = self.topology;
+        config.multi_thread = self.multi_thread;
+        if let Some(preset) = self.rules {
+            config.rules = ScoringRules::from_preset(preset);
+        }
+        if let Some(min_length) = self.min_length {
+            config.min_word_length = min_length;
+        }
+        config
+    }
+}
+
+// Generated boards are rerolled until they meet all of these
+#[derive(Args, Debug)]
+struct ConstraintArgs {
+    /// Only keep boards with at least this many words
+    #[arg(long, default_value_t = 0)]
+    min_words: usize,
+
+    /// Only keep boards worth at least this many points in total
+    #[arg(long, default_value_t = 0)]
+    min_score: u32,
+
+    /// Only keep boards with a word at least this long
+    #[arg(long, default_value_t = 0)]
+    long_word: usize,
+
+    /// Most times any one letter may appear on a board
+    #[arg(long)]
+    max_repeats: Option<usize>,
+
+    /// Most Qu dice a board may have
+    #[arg(long)]
+    max_qu: Option<usize>,
+
+    /// Boards to roll before giving up on meeting the constraints
+    #[arg(long, default_value_t = 10_000)]
+    attempts: usize,
+}
+
+impl ConstraintArgs {
+    fn to_constraints(&self) -> Option<BoardConstraints> {
+        let constraints = BoardConstraints {
+            min_words: self.min_words,
+            min_score: self.min_score,
+            min_longest_word: self.long_word,
+            max_repeats: self.max_repeats,
+            max_qu: self.max_qu,
+            max_attempts: self.attempts,
+        };
+        let unconstrained = constraints.min_words == 0
+            && constraints.min_score == 0
+            && constraints.min_longest_word == 0
+            && constraints.max_repeats.is_none()
+            && constraints.max_qu.is_none();
+        (!unconstrained).then_some(constraints)
+    }
+}
+
+#[derive(Args, Debug)]
+struct DictionaryArgs {
+    /// JSON word list: a path such as ./words.json, or a file name to look up in the data dirs
+    #[arg(long, default_value_t = String::from(DEFAULT_DICTIONARY))]
+    dictionary: String,
+
+    /// Directory to look for the dictionary in before $BOGGLE_DATA_DIR and the XDG data dirs
+    #[arg(long)]
+    data_dir: Option<PathBuf>,
+
+    /// Dictionary representation to solve with
+    #[arg(long, value_enum, default_value_t = TrieKind::Compact)]
+    trie: TrieKind,
+}
+
+impl DictionaryArgs {
+    fn location(&self) -> DictionaryLocation {
+        DictionaryLocation::new(&self.dictionary, self.data_dir.clone())
+    }
+}
+
+#[derive(ValueEnum, Clone, Copy, Debug)]
+enum TrieKind {
+    Compact,
+    HashMap,
+}
+
+fn exit_code(error: &BoggleError) -> ExitCode {
+    match error {
+        BoggleError::DictionaryIo(_) => ExitCode::from(2),
+        BoggleError::CorruptTrieCache(_) => ExitCode::from(3),
+        BoggleError::InvalidBoardChar(_)
+        | BoggleError::InvalidBoardFormat(_)
+        | BoggleError::InvalidGraph(_)
+        | BoggleError::InvalidDice(_) => ExitCode::from(4),
+        BoggleError::SizeMismatch { .. } => ExitCode::from(5),
+        BoggleError::UnsupportedSize(_) => ExitCode::from(6),
+        BoggleError::BoardIo(_) => ExitCode::from(7),
+        BoggleError::ConstraintsUnmet(_) => ExitCode::from(8),
+        BoggleError::CacheIo(_) => ExitCode::from(9),
+    }
+}
+
+fn main() -> ExitCode {
+    let cli = Cli::parse();
+    match run(cli.command) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            exit_code(&e)
+        }
+    }
+}
+
+fn run(command: Command) -> Result<(), BoggleError> {
+    let trie = match &command {
+        Command::Play { dictionary, .. }
+        | Command::Solve { dictionary, .. }
+        | Command::Batch { dictionary, .. }
+        | Command::Generate { dictionary, .. }
+        | Command::Search { dictionary, .. }
+        | Command::Bench { dictionary, .. } => dictionary.trie,
+        Command::Dict { .. } => TrieKind::Compact,
+    };
+    match trie {
+        TrieKind::Compact => run_with::<CompactTrie>(command),
+        TrieKind::HashMap => run_with::<DictTrie>(command),
+    }
+}
+
+fn run_with<D: WordTrie>(command: Command) -> Result<(), BoggleError> {
+    match command {
+        Command::Play {
+            time,
+            seed,
+            solver,
+            dictionary,
+        } => {
+            let seed = seed.unwrap_or_else(boggle_dice::random_seed);
+            let dictionary = Arc::new(D::load(&dictionary.location())?);
+            let mut config = solver.to_config()?;
+            config.letter_weights = solver.shape.letter_weights(&*dictionary);
+            let mut game = BoggleGame::new(config, time, dictionary, seed)?;
+            game.start();
+        }
+        Command::Solve {
+            board,
+            graph,
+            all_paths,
+            format,
+            solver,
+            dictionary,
+        } => {
+            let board = match graph {
+                Some(path) => fs::read_to_string(path).map_err(BoggleError::BoardIo)?,
+                None => board.unwrap_or_default(),
+            };
+            // A board string that gives its own dimensions wins over --size/--rows/--cols
+            let (rows, cols) = solver.shape.dimensions();
+            let board = Board::parse(&board, rows, cols, solver.shape.grid)?;
+            let (rows, cols) = board.dimensions();
+            let mut config = solver.to_config_for(rows, cols);
+            config.all_paths = all_paths;
+            let dictionary = Arc::new(D::load(&dictionary.location())?);
+            let start = Instant::now();
+            let solver = BoggleSolver::from_board(board, config, dictionary);
+            let report = solver.report(start.elapsed());
+            print_report(&report, format)?;
+        }
+        Command::Batch {
+            input,
+            format,
+            chunk_size,
+            solver,
+            dictionary,
+        } => {
+            let dictionary = Arc::new(D::load(&dictionary.location())?);
+            let reader: Box<dyn BufRead> = match input {
+                Some(path) if path != Path::new("-") => Box::new(BufReader::new(
+                    File::open(path).map_err(BoggleError::BoardIo)?,
+                )),
+                _ => Box::new(io::stdin().lock()),
+            };
+            run_batch(
+                reader,
+                format,
+                chunk_size,
+                &solver.to_config()?,
+                &dictionary,
+            )?;
+        }
+        Command::Generate {
+            solver,
+            seed,
+            count,
+            pretty,
+            constraints,
+            dictionary,
+        } => {
+            let seed = seed.unwrap_or_else(boggle_dice::random_seed);
+            eprintln!("Seed: {}", seed);
+            let mut rand = boggle_dice::seeded_rng(seed);
+            let mut config = solver.to_config()?;
+            let constraints = constraints.to_constraints();
+            let dictionary = if solver.shape.letter_frequency || constraints.is_some() {
+                Some(Arc::new(D::load(&dictionary.location())?))
+            } else {
+                None
+            };
+            if let Some(dictionary) = &dictionary {
+                config.letter_weights = solver.shape.letter_weights(&**dictionary);
+            }
+            let print_board = |board: &Board| {
+                println!("{}", board.to_board_string());
+                if pretty {
+                    println!("{}", board);
+                }
+            };
+            match (constraints, dictionary) {
+                (Some(constraints), Some(dictionary)) => {
+                    let mut solver = BoggleSolver::new(config, dictionary, &mut rand)?;
+                    for _ in 0..count {
+                        solver.reshuffle_until(&constraints, &mut rand)?;
+                        print_board(&solver.get_board());
+                    }
+                }
+                _ => {
+                    for _ in 0..count {
+                        print_board(&config.roll_board(&mut rand)?.into());
+                    }
+                }
+            }
+        }
+        Command::Search {
+            iterations,
+            chains,
+            temperature,
+            real_dice,
+            seed,
+            format,
+            solver,
+            dictionary,
+        } => {
+            let seed = seed.unwrap_or_else(boggle_dice::random_seed);
+            eprintln!("Seed: {}", seed);
+            let dictionary = Arc::new(D::load(&dictionary.location())?);
+            let mut config = solver.to_config()?;
+            config.letter_weights = solver.shape.letter_weights(&*dictionary);
+            let mut options = SearchOptions {
+                iterations,
+                start_temperature: temperature,
+                real_dice,
+                ..SearchOptions::default()
+            };
+            if let Some(chains) = chains {
+                options.chains = chains;
+            }
+            let start = Instant::now();
+            let best = board_search::find_best_board(&config, dictionary, &options, seed)?;
+            let report = best.report(start.elapsed());
+            // The board is the answer here, so lead with it
+            if format == OutputFormat::Text {
+                println!("{}\n{}", report.board_string, report.board);
+            }
+            print_report(&report, format)?;
+        }
+        Command::Dict { action, dictionary } => run_dict(action, &dictionary.location())?,
+        Command::Bench {
+            boards,
+            seed,
+            solver,
+            dictionary,
+        } => {
+            let seed = seed.unwrap_or_else(boggle_dice::random_seed);
+            let start = Instant::now();
+            let dictionary = Arc::new(D::load(&dictionary.location())?);
+            let load_time = start.elapsed();
+
+            let mut config = solver.to_config()?;
+            config.letter_weights = solver.shape.letter_weights(&*dictionary);
+            let mut rand = boggle_dice::seeded_rng(seed);
+            let mut solver = BoggleSolver::new(config.clone(), dictionary, &mut rand)?;
+            let mut total_words = 0;
+            let mut total_score = 0;
+            let start = Instant::now();
+            for _ in 0..boards {
+                solver.set_board(config.roll_board(&mut rand)?)?;
+                total_words += solver.get_possible_words().len();
+                total_score += solver.max_score();
+            }
+            let elapsed = start.elapsed().as_secs_f64();
+
+            println!("Seed: {}", seed);
+            println!(
+                "Dictionary loaded in {:.3} seconds",
+                load_time.as_secs_f64()
+            );
+            println!(
+                "Solved {} {}x{} boards in {:.3} seconds ({:.1} boards/s)",
+                boards,
+                config.rows,
+                config.cols,
+                elapsed,
+                boards as f64 / elapsed
+            );
+            println!(
+                "Average {:.1} words and {:.1} points per board",
+                total_words as f64 / boards.max(1) as f64,
+                total_score as f64 / boards.max(1) as f64
+            );
+        }
+    }
+    Ok(())
+}
+
+// Prints the report for a single board in the chosen format
+fn print_report(report: &SolveReport, format: OutputFormat) -> Result<(), BoggleError> {
+    match format {
+        OutputFormat::Text => {
+            println!("{}", report.to_text());
+            eprintln!("Time taken: {} seconds", report.elapsed_seconds);
+        }
+        OutputFormat::Json => println!(
+            "{}",
+            report
+                .to_json()
+                .map_err(|e| BoggleError::BoardIo(e.into()))?
+        ),
+        OutputFormat::Ndjson => println!(
+            "{}",
+            report
+                .to_ndjson()
+                .map_err(|e| BoggleError::BoardIo(e.into()))?
+        ),
+        // CSV only carries the word table, so the rest goes where it can't get mixed in
+        OutputFormat::Csv => {
+            print!("{}", report.to_csv());
+            eprintln!("Board: {}", report.board_string);
+            eprintln!("{} words, {}", report.word_count, report.summary());
+            eprintln!("Time taken: {} seconds", report.elapsed_seconds);
+        }
+    }
+    Ok(())
+}
+
+// Solves the boards a chunk at a time so results start streaming out before the input ends and
+// memory stays flat however long the input is
+fn run_batch<D: WordTrie>(
+    reader: impl BufRead,
+    format: OutputFormat,
+    chunk_size: usize,
+    config: &SolverConfig,
+    dictionary: &Arc<D>,
+) -> Result<(), BoggleError> {
+    let mut out = io::stdout().lock();
+    if format == OutputFormat::Csv {
+        writeln!(out, "{}", BoardSummary::CSV_HEADER).map_err(BoggleError::BoardIo)?;
+    }
+
+    let mut lines = reader.lines().enumerate();
+    loop {
+        let mut chunk: Vec<(usize, String)> = Vec::with_capacity(chunk_size);
+        for (index, line) in lines.by_ref() {
+            let line = line.map_err(BoggleError::BoardIo)?;
+            let board = line.trim();
+            if !board.is_empty() {
+                chunk.push((index + 1, board.to_string()));
+            }
+            if chunk.len() >= chunk_size.max(1) {
+                break;
+            }
+        }
+        if chunk.is_empty() {
+            return Ok(());
+        }
+
+        for summary in boggle::solve_batch(&chunk, config, dictionary) {
+            let line = match format {
+                OutputFormat::Csv => summary.to_csv_row(),
+                OutputFormat::Text => summary.to_text(),
+                // A batch is streamed, so JSON comes out as one object per line
+                OutputFormat::Json | OutputFormat::Ndjson => {
+                    serde_json::to_string(&summary).map_err(|e| BoggleError::BoardIo(e.into()))?
+                }
+            };
+            writeln!(out, "{}", line).map_err(BoggleError::BoardIo)?;
+        }
+        out.flush().map_err(BoggleError::BoardIo)?;
+    }
+}
+
+fn run_dict(action: Option<DictAction>, location: &DictionaryLocation) -> Result<(), BoggleError> {
+    match action {
+        None => utils::trie_cli(location),
+        Some(DictAction::Build) => {
+            trie_manager::clear_cache(location)?;
+            let start = Instant::now();
+            trie_manager::load_trie(location)?;
+            let compact = trie_manager::load_compact_trie(location)?;
+            println!(
+                "Built the dictionary caches in {:.3} seconds ({} DAWG nodes)",
+                start.elapsed().as_secs_f64(),
+                compact.node_count()
+            );
+        }
+        Some(DictAction::Check { words }) => {
+            let trie = trie_manager::load_compact_trie(location)?;
+            for word in words {
+                let word = word.to_lowercase();
+                let found = if trie.contains_word(&word) {
+                    "yes"
+                } else {
+                    "no"
+                };
+                println!("{} {}", word, found);
+            }
+        }
+        Some(DictAction::Extend { prefix }) => {
+            let trie = trie_manager::load_trie(location)?;
+            let mut extensions = trie.extend_word(&prefix.to_lowercase());
+            extensions.sort();
+            for word in extensions {
+                println!("{}", word);
+            }
+        }
+        Some(DictAction::Insert { words }) => {
+            let added = trie_manager::insert_words(location, &words)?;
+            println!("Added {} new words to the dictionary", added);
+        }
     }
+    Ok(())
 }
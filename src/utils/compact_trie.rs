@@ -0,0 +1,168 @@
+use super::dict_trie::{DictTrie, DictTrieNode};
+use super::resources::DictionaryLocation;
+use super::trie_manager;
+use super::word_trie::WordTrie;
+use crate::error::BoggleError;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+const END_OF_WORD: u32 = 1 << 31;
+
+// Each node keeps a bitmap of which of a-z it has children for. The children's ids sit next to
+// each other in `edges`, so the child for a letter is found by counting the bits below it.
+#[derive(Deserialize, Serialize, Clone, Copy, Debug)]
+struct CompactNode {
+    mask: u32,
+    first_edge: u32,
+}
+
+// A minimised DAWG: identical suffixes (all the "-ing"s, "-ness"es and so on) are stored once
+// and shared, and nodes are plain indices into flat arrays rather than nested hash maps.
+#[derive(Deserialize, Serialize, Clone, Debug)]
+pub struct CompactTrie {
+    nodes: Vec<CompactNode>,
+    edges: Vec<u32>,
+    root: u32,
+}
+
+impl CompactTrie {
+    pub fn from_trie(trie: &DictTrie) -> Self {
+        let mut builder = CompactTrieBuilder {
+            nodes: vec![],
+            edges: vec![],
+            registry: HashMap::new(),
+        };
+        let root = builder.add(trie.get_start_node());
+        Self {
+            nodes: builder.nodes,
+            edges: builder.edges,
+            root,
+        }
+    }
+
+    pub fn node_count(&self) -> usize {
+        self.nodes.len()
+    }
+
+    pub fn edge_count(&self) -> usize {
+        self.edges.len()
+    }
+}
+
+struct CompactTrieBuilder {
+    nodes: Vec<CompactNode>,
+    edges: Vec<u32>,
+    // Nodes are identified by whether they end a word and where each of their letters lead
+    registry: HashMap<(bool, Vec<(u32, u32)>), u32>,
+}
+
+impl CompactTrieBuilder {
+    // Adds the children first so that equal subtrees already have equal ids by the time their
+    // parents are compared
+    fn add(&mut self, node: &DictTrieNode) -> u32 {
+        let mut children: Vec<(u32, u32)> = node
+            .children()
+            .filter_map(|(letter, child)| letter_index(*letter).map(|index| (index, child)))
+            .map(|(index, child)| (index, self.add(child)))
+            .collect();
+        children.sort_unstable();
+
+        let key = (node.end, children);
+        if let Some(&id) = self.registry.get(&key) {
+            return id;
+        }
+
+        let mut mask = if node.end { END_OF_WORD } else { 0 };
+        for (index, _) in &key.1 {
+            mask |= 1 << index;
+        }
+        let first_edge = self.edges.len() as u32;
+        self.edges.extend(key.1.iter().map(|(_, child)| *child));
+
+        let id = self.nodes.len() as u32;
+        self.nodes.push(CompactNode { mask, first_edge });
+        self.registry.insert(key, id);
+        id
+    }
+}
+
+// Only a-z can appear on a board, so anything else in the word list is dropped
+fn letter_index(letter: char) -> Option<u32> {
+    letter
+        .is_ascii_lowercase()
+        .then(|| letter as u32 - 'a' as u32)
+}
+
+impl WordTrie for CompactTrie {
+    type Node<'a> = u32;
+
+    fn load(location: &DictionaryLocation) -> Result<Self, BoggleError> {
+        trie_manager::load_compact_trie(location)
+    }
+
+    fn root(&self) -> u32 {
+        self.root
+    }
+
+    fn child(&self, node: u32, letter: char) -> Option<u32> {
+        let index = letter_index(letter)?;
+        let node = self.nodes[node as usize];
+        if node.mask & (1 << index) == 0 {
+            return None;
+        }
+        let offset = (node.mask & ((1 << index) - 1)).count_ones();
+        Some(self.edges[(node.first_edge + offset) as usize])
+    }
+
+    fn is_word(&self, node: u32) -> bool {
+        self.nodes[node as usize].mask & END_OF_WORD != 0
+    }
+
+    // The children sit in `edges` in letter order, so walking the set bits lines up with them
+    fn children(&self, node: u32) -> impl Iterator<Item = (char, u32)> + '_ {
+        let node = self.nodes[node as usize];
+        (0..26u32)
+            .filter(move |index| node.mask & (1 << index) != 0)
+            .enumerate()
+            .map(move |(offset, index)| {
+                let letter = char::from(b'a' + index as u8);
+                (letter, self.edges[node.first_edge as usize + offset])
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn matches_the_hash_map_trie() {
+        let words = [
+            "test", "testing", "tested", "resting", "rested", "dog", "door", "dot",
+        ];
+        let mut trie = DictTrie::new();
+        for word in words {
+            trie.insert_word(word);
+        }
+        let compact = CompactTrie::from_trie(&trie);
+
+        for word in words {
+            assert!(compact.contains_word(word));
+        }
+        for word in ["", "te", "tests", "rest", "do", "doors", "Test"] {
+            assert_eq!(compact.contains_word(word), trie.contains_word(word));
+        }
+        // The shared "ing"/"ed" endings mean far fewer nodes than letters inserted
+        assert!(compact.node_count() < 20);
+
+        let after_do: Vec<char> = compact
+            .children(
+                compact
+                    .child(compact.child(compact.root(), 'd').unwrap(), 'o')
+                    .unwrap(),
+            )
+            .map(|(letter, _)| letter)
+            .collect();
+        assert_eq!(after_do, vec!['g', 'o', 't']);
+    }
+}
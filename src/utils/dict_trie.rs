@@ -1,7 +1,11 @@
+use super::resources::DictionaryLocation;
+use super::trie_manager;
+use super::word_trie::WordTrie;
+use crate::error::BoggleError;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
-#[derive(Deserialize, Serialize, Clone, Debug)]
+#[derive(Deserialize, Serialize, Clone, Debug, Default)]
 pub struct DictTrieNode {
     children: HashMap<char, DictTrieNode>,
     pub end: bool,
@@ -16,11 +20,15 @@ impl DictTrieNode {
     }
 
     pub fn get_child(&self, letter: &char) -> Option<&Self> {
-        self.children.get(&letter)
+        self.children.get(letter)
+    }
+
+    pub fn children(&self) -> impl Iterator<Item = (&char, &DictTrieNode)> {
+        self.children.iter()
     }
 }
 
-#[derive(Deserialize, Serialize, Clone, Debug)]
+#[derive(Deserialize, Serialize, Clone, Debug, Default)]
 pub struct DictTrie {
     root: DictTrieNode,
 }
@@ -35,10 +43,7 @@ impl DictTrie {
     pub fn insert_word(&mut self, word: &str) {
         let mut current_node = &mut self.root;
         for c in word.chars() {
-            current_node = current_node
-                .children
-                .entry(c)
-                .or_insert_with(DictTrieNode::new);
+            current_node = current_node.children.entry(c).or_default();
         }
         current_node.end = true;
     }
@@ -84,6 +89,36 @@ impl DictTrie {
     }
 }
 
+impl WordTrie for DictTrie {
+    type Node<'a> = &'a DictTrieNode;
+
+    fn load(location: &DictionaryLocation) -> Result<Self, BoggleError> {
+        trie_manager::load_trie(location)
+    }
+
+    fn root(&self) -> &DictTrieNode {
+        &self.root
+    }
+
+    fn child<'a>(&'a self, node: &'a DictTrieNode, letter: char) -> Option<&'a DictTrieNode> {
+        node.children.get(&letter)
+    }
+
+    fn is_word(&self, node: &DictTrieNode) -> bool {
+        node.end
+    }
+
+    fn children<'a>(
+        &'a self,
+        node: &'a DictTrieNode,
+    ) -> impl Iterator<Item = (char, &'a DictTrieNode)> + 'a {
+        node.children
+            .iter()
+            .filter(|(letter, _)| letter.is_ascii_lowercase())
+            .map(|(&letter, child)| (letter, child))
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -91,7 +126,7 @@ mod tests {
 
     #[test]
     fn checking_words() {
-        let test_words: Vec<String> = vec!["Test", "Testing", "Taught", "Dog", "Door", "Dot"]
+        let test_words: Vec<String> = ["Test", "Testing", "Taught", "Dog", "Door", "Dot"]
             .iter()
             .map(|&s| String::from(s))
             .collect();
@@ -100,18 +135,18 @@ mod tests {
             trie.insert_word(&word);
         }
 
-        assert_eq!(trie.check_word(&String::from("Test")), true);
-        assert_eq!(trie.check_word(&String::from("Testing")), true);
-        assert_eq!(trie.check_word(&String::from("Dog")), true);
-        assert_eq!(trie.check_word(&String::from("Do")), false);
-        assert_eq!(trie.check_word(&String::from("Test")), true);
-        assert_eq!(trie.check_word(&String::from("Dogs")), false);
-        assert_eq!(trie.check_word(&String::from("")), false);
+        assert!(trie.check_word(&String::from("Test")));
+        assert!(trie.check_word(&String::from("Testing")));
+        assert!(trie.check_word(&String::from("Dog")));
+        assert!(!trie.check_word(&String::from("Do")));
+        assert!(trie.check_word(&String::from("Test")));
+        assert!(!trie.check_word(&String::from("Dogs")));
+        assert!(!trie.check_word(""));
     }
 
     #[test]
     fn test_extend_word() {
-        let test_words: Vec<String> = vec!["Test", "Testing", "Taught", "Dog", "Door", "Dot"]
+        let test_words: Vec<String> = ["Test", "Testing", "Taught", "Dog", "Door", "Dot"]
             .iter()
             .map(|&s| String::from(s))
             .collect();
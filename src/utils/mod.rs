@@ -1,11 +1,17 @@
 use std::io::{self, Write};
 
+pub mod compact_trie;
+
 pub mod dict_trie;
 
+pub mod resources;
+
 pub mod trie_manager;
 
-pub fn trie_cli() {
-    let mut trie = match trie_manager::load_trie(String::from("./dictionary.json")) {
+pub mod word_trie;
+
+pub fn trie_cli(location: &resources::DictionaryLocation) {
+    let mut trie = match trie_manager::load_trie(location) {
         Ok(trie) => {
             println!("Trie loaded successfully. Ready for lookups.");
             trie
@@ -0,0 +1,176 @@
+use crate::error::BoggleError;
+use std::env;
+use std::io;
+use std::path::{self, Path, PathBuf};
+
+pub const DEFAULT_DICTIONARY: &str = "dictionary.json";
+pub const DATA_DIR_ENV: &str = "BOGGLE_DATA_DIR";
+const APP_DIR: &str = "boggle";
+
+#[cfg(feature = "embedded-dictionary")]
+const EMBEDDED_DICTIONARY: &[u8] = include_bytes!("../../resources/dictionary.json");
+
+// Which dictionary to load and, optionally, an explicit directory to look for it in
+#[derive(Clone, Debug)]
+pub struct DictionaryLocation {
+    pub dictionary: String,
+    pub data_dir: Option<PathBuf>,
+}
+
+impl DictionaryLocation {
+    pub fn new(dictionary: &str, data_dir: Option<PathBuf>) -> Self {
+        Self {
+            dictionary: dictionary.to_string(),
+            data_dir,
+        }
+    }
+}
+
+impl Default for DictionaryLocation {
+    fn default() -> Self {
+        Self::new(DEFAULT_DICTIONARY, None)
+    }
+}
+
+pub enum DictionarySource {
+    File(PathBuf),
+    Embedded(&'static [u8]),
+}
+
+// Finds a dictionary by trying, in order: the name as a path, the explicit data directory,
+// $BOGGLE_DATA_DIR, the XDG data directories, the resources folder of a source checkout in debug
+// builds and finally the dictionary compiled into the binary with the `embedded-dictionary` feature.
+// Only absolute names or ones with a directory in them, e.g. "./words.json", count as paths, so
+// that a stray file in the working directory can't shadow the data directories.
+pub fn resolve_dictionary(location: &DictionaryLocation) -> Result<DictionarySource, BoggleError> {
+    let given = PathBuf::from(&location.dictionary);
+    let is_path = given.is_absolute() || location.dictionary.contains(path::is_separator);
+    if is_path && given.is_file() {
+        return Ok(DictionarySource::File(given));
+    }
+
+    let candidates: Vec<PathBuf> = data_dirs(location.data_dir.as_deref())
+        .into_iter()
+        .map(|dir| dir.join(&location.dictionary))
+        .collect();
+    if let Some(found) = candidates.iter().find(|path| path.is_file()) {
+        return Ok(DictionarySource::File(found.clone()));
+    }
+
+    if let Some(embedded) = embedded_dictionary(&location.dictionary) {
+        return Ok(DictionarySource::Embedded(embedded));
+    }
+
+    let searched: Vec<String> = candidates
+        .iter()
+        .map(|path| path.display().to_string())
+        .collect();
+    Err(BoggleError::DictionaryIo(io::Error::new(
+        io::ErrorKind::NotFound,
+        format!(
+            "could not find dictionary '{}' (searched {})",
+            location.dictionary,
+            searched.join(", ")
+        ),
+    )))
+}
+
+fn data_dirs(explicit: Option<&Path>) -> Vec<PathBuf> {
+    let mut dirs: Vec<PathBuf> = vec![];
+    if let Some(dir) = explicit {
+        dirs.push(dir.to_path_buf());
+    }
+    if let Some(dir) = env_path(DATA_DIR_ENV) {
+        dirs.push(dir);
+    }
+    if let Some(dir) = xdg_home("XDG_DATA_HOME", ".local/share") {
+        dirs.push(dir.join(APP_DIR));
+    }
+    let system_dirs =
+        env::var("XDG_DATA_DIRS").unwrap_or_else(|_| String::from("/usr/local/share:/usr/share"));
+    for dir in env::split_paths(&system_dirs) {
+        if dir.is_absolute() {
+            dirs.push(dir.join(APP_DIR));
+        }
+    }
+    // Running from a checkout, e.g. through `cargo run`. Release builds leave this out so that an
+    // installed binary never reads from wherever it happened to be compiled.
+    #[cfg(debug_assertions)]
+    dirs.push(Path::new(env!("CARGO_MANIFEST_DIR")).join("resources"));
+    dirs
+}
+
+// Caches are written to $XDG_CACHE_HOME/boggle (usually ~/.cache/boggle) so that the data
+// directories can stay read only
+pub fn cache_dir() -> PathBuf {
+    match xdg_home("XDG_CACHE_HOME", ".cache") {
+        Some(dir) => dir.join(APP_DIR),
+        None => env::temp_dir().join(APP_DIR),
+    }
+}
+
+fn xdg_home(variable: &str, fallback: &str) -> Option<PathBuf> {
+    env_path(variable).or_else(|| env_path("HOME").map(|home| home.join(fallback)))
+}
+
+// The XDG spec says relative paths in these variables are invalid and should be ignored
+fn env_path(variable: &str) -> Option<PathBuf> {
+    env::var_os(variable)
+        .map(PathBuf::from)
+        .filter(|path| path.is_absolute())
+}
+
+#[cfg(feature = "embedded-dictionary")]
+fn embedded_dictionary(dictionary: &str) -> Option<&'static [u8]> {
+    (dictionary == DEFAULT_DICTIONARY).then_some(EMBEDDED_DICTIONARY)
+}
+
+#[cfg(not(feature = "embedded-dictionary"))]
+fn embedded_dictionary(_dictionary: &str) -> Option<&'static [u8]> {
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    fn resolved(location: &DictionaryLocation) -> PathBuf {
+        match resolve_dictionary(location).unwrap() {
+            DictionarySource::File(path) => path,
+            DictionarySource::Embedded(_) => panic!("expected a dictionary file"),
+        }
+    }
+
+    #[test]
+    fn lookup_order() {
+        let root = env::temp_dir().join(format!("boggle-resources-test-{}", std::process::id()));
+        let (explicit, data, xdg) = (root.join("explicit"), root.join("data"), root.join("xdg"));
+        // Cargo.toml sits in the working directory while tests run, so a bare name must not pick
+        // it up from there
+        let name = "Cargo.toml";
+        for dir in [&explicit, &data, &xdg.join(APP_DIR)] {
+            fs::create_dir_all(dir).unwrap();
+            fs::write(dir.join(name), "[]").unwrap();
+        }
+        env::set_var(DATA_DIR_ENV, &data);
+        env::set_var("XDG_DATA_HOME", &xdg);
+        env::set_var("XDG_DATA_DIRS", root.join("system"));
+
+        let location = DictionaryLocation::new(name, Some(explicit.clone()));
+        assert_eq!(resolved(&location), explicit.join(name));
+        let location = DictionaryLocation::new(name, None);
+        assert_eq!(resolved(&location), data.join(name));
+        env::remove_var(DATA_DIR_ENV);
+        assert_eq!(resolved(&location), xdg.join(APP_DIR).join(name));
+        // Naming a directory makes it a path again
+        assert_eq!(
+            resolved(&DictionaryLocation::new("./Cargo.toml", None)),
+            PathBuf::from("./Cargo.toml")
+        );
+
+        env::remove_var("XDG_DATA_HOME");
+        env::remove_var("XDG_DATA_DIRS");
+        fs::remove_dir_all(&root).unwrap();
+    }
+}
@@ -1,56 +1,315 @@
+use super::compact_trie::CompactTrie;
 use super::dict_trie::DictTrie;
+use super::resources::{self, DictionaryLocation, DictionarySource};
+use crate::error::BoggleError;
+use serde::de::DeserializeOwned;
 use serde::{Deserialize, Serialize};
 use serde_json;
+use std::collections::HashSet;
 use std::fs;
-use std::io::{self, Write};
+use std::io::{self, BufReader, BufWriter, Write};
 use std::path::{Path, PathBuf};
+use std::time::SystemTime;
 
-const TRIE_FILE: &str = "resources/english_trie.bin";
+// Written at the start of every cache file so a stale cache can be spotted without decoding the trie
+#[derive(Serialize, Deserialize, PartialEq, Debug)]
+struct CacheHeader {
+    format_version: u32,
+    source_path: String,
+    source_hash: u64,
+}
+
+// A trie that can be built from a word list and cached on disk in its own format
+trait CachedTrie: Serialize + DeserializeOwned {
+    const EXTENSION: &'static str;
+    // Bump this whenever the layout of the cache file or the trie changes
+    const FORMAT_VERSION: u32;
 
-#[derive(Serialize, Deserialize)]
-struct SerializableTrie {
-    trie: DictTrie,
+    fn build(words: Vec<String>) -> Self;
 }
 
-pub fn load_trie(dictionary_path: String) -> Result<DictTrie, io::Error> {
-    let exe_dir = std::env::current_exe()?
-        .parent()
-        .and_then(Path::parent)
-        .and_then(Path::parent)
-        .unwrap()
-        .to_path_buf();
-    let file_path: PathBuf = exe_dir.join("resources").join(dictionary_path);
-    if Path::new(TRIE_FILE).exists() {
-        let data = fs::read(TRIE_FILE)?;
-        let serializable_trie: SerializableTrie = bincode::deserialize(&data)
-            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
-        Ok(serializable_trie.trie)
-    } else {
-        let trie = create_and_serialize_trie(file_path)?;
-        Ok(trie)
+impl CachedTrie for DictTrie {
+    const EXTENSION: &'static str = "bin";
+    const FORMAT_VERSION: u32 = 2;
+
+    fn build(words: Vec<String>) -> Self {
+        let mut trie = DictTrie::new();
+        for word in words {
+            trie.insert_word(&word);
+        }
+        trie
+    }
+}
+
+impl CachedTrie for CompactTrie {
+    const EXTENSION: &'static str = "dawg";
+    const FORMAT_VERSION: u32 = 1;
+
+    fn build(words: Vec<String>) -> Self {
+        CompactTrie::from_trie(&DictTrie::build(words))
     }
 }
 
-fn create_and_serialize_trie(dictionary_path: PathBuf) -> Result<DictTrie, io::Error> {
-    let words: Vec<String> = load_words_from_json(dictionary_path)?;
+pub fn load_trie(location: &DictionaryLocation) -> Result<DictTrie, BoggleError> {
+    load(location)
+}
+
+pub fn load_compact_trie(location: &DictionaryLocation) -> Result<CompactTrie, BoggleError> {
+    load(location)
+}
+
+// Loads the trie for a dictionary file, going through its cache when the cache is still fresh
+pub fn load_trie_from(source_path: &Path, cache_dir: &Path) -> Result<DictTrie, BoggleError> {
+    load_from(source_path, cache_dir)
+}
+
+pub fn load_compact_trie_from(
+    source_path: &Path,
+    cache_dir: &Path,
+) -> Result<CompactTrie, BoggleError> {
+    load_from(source_path, cache_dir)
+}
 
-    let mut trie = DictTrie::new();
+fn load<T: CachedTrie>(location: &DictionaryLocation) -> Result<T, BoggleError> {
+    match resources::resolve_dictionary(location)? {
+        DictionarySource::File(path) => load_from(&path, &resources::cache_dir()),
+        DictionarySource::Embedded(source) => load_from_bytes(
+            &format!("embedded:{}", location.dictionary),
+            source,
+            None,
+            &resources::cache_dir(),
+        ),
+    }
+}
+
+// Deletes the cached tries for a dictionary so that the next load rebuilds them from the source
+pub fn clear_cache(location: &DictionaryLocation) -> Result<(), BoggleError> {
+    let source_name = match resources::resolve_dictionary(location)? {
+        DictionarySource::File(path) => fs::canonicalize(path)?.to_string_lossy().into_owned(),
+        DictionarySource::Embedded(_) => format!("embedded:{}", location.dictionary),
+    };
+    for extension in [DictTrie::EXTENSION, CompactTrie::EXTENSION] {
+        let cache_path = cache_path_for(&source_name, &resources::cache_dir(), extension);
+        if cache_path.exists() {
+            fs::remove_file(cache_path).map_err(BoggleError::CacheIo)?;
+        }
+    }
+    Ok(())
+}
+
+// Adds words to a dictionary file, returning how many were new. The caches notice the change
+// through the content hash, so nothing else needs to be invalidated.
+pub fn insert_words(location: &DictionaryLocation, words: &[String]) -> Result<usize, BoggleError> {
+    let path = match resources::resolve_dictionary(location)? {
+        DictionarySource::File(path) => path,
+        DictionarySource::Embedded(_) => {
+            return Err(BoggleError::DictionaryIo(io::Error::new(
+                io::ErrorKind::Unsupported,
+                "the embedded dictionary cannot be edited, pass a dictionary file instead",
+            )))
+        }
+    };
+    let mut existing: Vec<String> = serde_json::from_slice(&fs::read(&path)?)?;
+    let mut known: HashSet<String> = existing.iter().cloned().collect();
+    let mut added = 0;
     for word in words {
-        trie.insert_word(&word.to_string());
+        let word = word.trim().to_lowercase();
+        if !word.is_empty() && known.insert(word.clone()) {
+            existing.push(word);
+            added += 1;
+        }
+    }
+    if added > 0 {
+        // Keep the file sorted and in the same layout as the bundled dictionary
+        existing.sort();
+        let writer = BufWriter::new(fs::File::create(&path)?);
+        let formatter = serde_json::ser::PrettyFormatter::with_indent(b"    ");
+        existing.serialize(&mut serde_json::Serializer::with_formatter(
+            writer, formatter,
+        ))?;
     }
+    Ok(added)
+}
 
-    let serializable_trie = SerializableTrie { trie: trie.clone() };
-    let serialized_data = bincode::serialize(&serializable_trie)
-        .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
-    let mut file = fs::File::create(TRIE_FILE)?;
-    file.write_all(&serialized_data)?;
+fn load_from<T: CachedTrie>(source_path: &Path, cache_dir: &Path) -> Result<T, BoggleError> {
+    let source_path = fs::canonicalize(source_path)?;
+    let source = fs::read(&source_path)?;
+    let source_modified = fs::metadata(&source_path)?.modified()?;
+    load_from_bytes(
+        &source_path.to_string_lossy(),
+        &source,
+        Some(source_modified),
+        cache_dir,
+    )
+}
+
+fn load_from_bytes<T: CachedTrie>(
+    source_name: &str,
+    source: &[u8],
+    source_modified: Option<SystemTime>,
+    cache_dir: &Path,
+) -> Result<T, BoggleError> {
+    let header = CacheHeader {
+        format_version: T::FORMAT_VERSION,
+        source_path: source_name.to_string(),
+        source_hash: fnv1a_hash(source),
+    };
+    let cache_path = cache_path_for(source_name, cache_dir, T::EXTENSION);
+
+    // A cache that was cut short or damaged is no worse than a stale one, so rebuild it
+    let fresh = cache_is_fresh(&cache_path, source_modified, &header);
+    if fresh {
+        if let Some(trie) = read_cache(&cache_path) {
+            return Ok(trie);
+        }
+    }
 
+    let words: Vec<String> = serde_json::from_slice(source)?;
+    let trie = T::build(words);
+    // The cache only saves time on the next load, so a read only cache directory is not an error
+    match write_cache(&cache_path, &header, &trie) {
+        Err(e) => eprintln!(
+            "Could not write the trie cache {}: {}",
+            cache_path.display(),
+            e
+        ),
+        // If the rewritten cache is unreadable too, the cache format itself is broken and every
+        // load would silently rebuild, so say so rather than papering over it
+        Ok(()) if fresh && read_cache::<T>(&cache_path).is_none() => {
+            return Err(BoggleError::CorruptTrieCache(format!(
+                "{} could not be decoded even after it was rebuilt",
+                cache_path.display()
+            )))
+        }
+        Ok(()) => {}
+    }
     Ok(trie)
 }
 
-fn load_words_from_json(file_path: PathBuf) -> Result<Vec<String>, io::Error> {
-    let file = fs::File::open(file_path)?;
-    let reader = io::BufReader::new(file);
-    let vec: Vec<String> = serde_json::from_reader(reader)?;
-    Ok(vec)
+// Each dictionary gets its own cache file, named after the file and a hash of where it came from
+fn cache_path_for(source_name: &str, cache_dir: &Path, extension: &str) -> PathBuf {
+    let stem = Path::new(source_name)
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().into_owned())
+        .unwrap_or_else(|| String::from("dictionary"));
+    let name_hash = fnv1a_hash(source_name.as_bytes());
+    cache_dir.join(format!("{}-{:016x}.{}", stem, name_hash, extension))
+}
+
+fn cache_is_fresh(
+    cache_path: &Path,
+    source_modified: Option<SystemTime>,
+    header: &CacheHeader,
+) -> bool {
+    let Ok(cache_modified) = fs::metadata(cache_path).and_then(|metadata| metadata.modified())
+    else {
+        return false;
+    };
+    if source_modified.is_some_and(|modified| modified > cache_modified) {
+        return false;
+    }
+    // An unreadable header means the cache was written by an older format, so just rebuild it
+    let Ok(file) = fs::File::open(cache_path) else {
+        return false;
+    };
+    bincode::deserialize_from::<_, CacheHeader>(BufReader::new(file))
+        .is_ok_and(|cached| cached == *header)
+}
+
+fn read_cache<T: CachedTrie>(cache_path: &Path) -> Option<T> {
+    let mut reader = BufReader::new(fs::File::open(cache_path).ok()?);
+    bincode::deserialize_from::<_, CacheHeader>(&mut reader)
+        .and_then(|_| bincode::deserialize_from::<_, T>(&mut reader))
+        .ok()
+}
+
+// Writes to a temporary file next to the cache and renames it into place, so that another process
+// loading the same dictionary never sees a half written cache
+fn write_cache<T: CachedTrie>(cache_path: &Path, header: &CacheHeader, trie: &T) -> io::Result<()> {
+    let cache_dir = cache_path.parent().unwrap_or_else(|| Path::new("."));
+    fs::create_dir_all(cache_dir)?;
+    let file_name = cache_path
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    let temp_path = cache_dir.join(format!(".{}.{}.tmp", file_name, std::process::id()));
+    let written = write_cache_file(&temp_path, header, trie)
+        .and_then(|()| fs::rename(&temp_path, cache_path));
+    if written.is_err() {
+        let _ = fs::remove_file(&temp_path);
+    }
+    written
+}
+
+fn write_cache_file<T: CachedTrie>(path: &Path, header: &CacheHeader, trie: &T) -> io::Result<()> {
+    let mut writer = BufWriter::new(fs::File::create(path)?);
+    bincode::serialize_into(&mut writer, header)
+        .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+    bincode::serialize_into(&mut writer, trie)
+        .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+    writer.flush()?;
+    Ok(())
+}
+
+// FNV-1a is stable across builds and platforms, unlike the std hasher, so it is safe to persist
+fn fnv1a_hash(bytes: &[u8]) -> u64 {
+    let mut hash: u64 = 0xcbf29ce484222325;
+    for byte in bytes {
+        hash ^= *byte as u64;
+        hash = hash.wrapping_mul(0x100000001b3);
+    }
+    hash
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::word_trie::WordTrie;
+
+    #[test]
+    fn cache_follows_the_source_dictionary() {
+        let dir = std::env::temp_dir().join(format!("boggle-cache-test-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let dictionary = dir.join("words.json");
+        let cache_dir = dir.join("cache");
+
+        fs::write(&dictionary, r#"["cat", "dog"]"#).unwrap();
+        let trie = load_trie_from(&dictionary, &cache_dir).unwrap();
+        assert!(trie.check_word("cat"));
+        let source_name = fs::canonicalize(&dictionary).unwrap();
+        assert!(cache_path_for(&source_name.to_string_lossy(), &cache_dir, "bin").exists());
+
+        // Loading again goes through the cache
+        assert!(load_trie_from(&dictionary, &cache_dir)
+            .unwrap()
+            .check_word("dog"));
+
+        // Changing the word list must not return the stale cache
+        fs::write(&dictionary, r#"["cow"]"#).unwrap();
+        let trie = load_trie_from(&dictionary, &cache_dir).unwrap();
+        assert!(trie.check_word("cow"));
+        assert!(!trie.check_word("cat"));
+
+        // The compact trie keeps its own cache next to the hash map one
+        let compact = load_compact_trie_from(&dictionary, &cache_dir).unwrap();
+        assert!(compact.contains_word("cow"));
+        assert!(cache_path_for(&source_name.to_string_lossy(), &cache_dir, "dawg").exists());
+
+        // A cache cut short after its header is rebuilt rather than reported as corrupt
+        let cache_path = cache_path_for(&source_name.to_string_lossy(), &cache_dir, "bin");
+        let cached = fs::read(&cache_path).unwrap();
+        fs::write(&cache_path, &cached[..cached.len() - 4]).unwrap();
+        assert!(load_trie_from(&dictionary, &cache_dir)
+            .unwrap()
+            .check_word("cow"));
+        assert_eq!(fs::read(&cache_path).unwrap(), cached);
+
+        // A cache directory that can't be created still leaves the freshly built trie to use
+        let unwritable = dictionary.join("cache");
+        assert!(load_trie_from(&dictionary, &unwritable)
+            .unwrap()
+            .check_word("cow"));
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
 }
@@ -0,0 +1,37 @@
+use super::resources::DictionaryLocation;
+use crate::error::BoggleError;
+
+// What the solver needs from a dictionary: start at the root and follow one letter at a time.
+// Implemented by the editable `DictTrie` and the read only `CompactTrie`.
+pub trait WordTrie: Send + Sync {
+    type Node<'a>: Copy
+    where
+        Self: 'a;
+
+    fn load(location: &DictionaryLocation) -> Result<Self, BoggleError>
+    where
+        Self: Sized;
+
+    fn root(&self) -> Self::Node<'_>;
+
+    fn child<'a>(&'a self, node: Self::Node<'a>, letter: char) -> Option<Self::Node<'a>>;
+
+    fn is_word<'a>(&'a self, node: Self::Node<'a>) -> bool;
+
+    // Every a-z letter that can follow `node` along with where it leads, for wildcard dice
+    fn children<'a>(
+        &'a self,
+        node: Self::Node<'a>,
+    ) -> impl Iterator<Item = (char, Self::Node<'a>)> + 'a;
+
+    fn contains_word(&self, word: &str) -> bool {
+        let mut node = self.root();
+        for letter in word.chars() {
+            match self.child(node, letter) {
+                Some(child) => node = child,
+                None => return false,
+            }
+        }
+        self.is_word(node)
+    }
+}